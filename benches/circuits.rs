@@ -0,0 +1,143 @@
+//! Keygen, proving and verification time for the tutorial circuits.
+//!
+//! Every benchmark id carries the circuit parameters and the `k` they need, so
+//! the report shows how row usage scales. Proof sizes are printed to stderr
+//! because criterion only measures time.
+
+mod fibo_regions;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use halo2_proofs::{
+    pairing::bn256::Fr as Fp,
+    plonk::{keygen_pk, keygen_vk, Circuit},
+};
+use halo_tutorial::{
+    circuits::{
+        arithmetic::MyCircuit,
+        custom_fibo::{get_sequence, CustomFiboCircuit},
+        fibo::{get_fibo_seq, FiboCircuit},
+        fibo_column::FiboColumnCircuit,
+    },
+    prover,
+};
+
+use fibo_regions::FiboRegionsCircuit;
+
+/// Benchmarks all three steps for one circuit at the smallest `k` it fits in.
+fn bench_circuit<C: Circuit<Fp> + Clone>(
+    c: &mut Criterion,
+    group: &str,
+    label: String,
+    circuit: C,
+    public_inputs: Vec<Fp>,
+) {
+    let instances: &[&[Fp]] = &[&public_inputs];
+    let k = match prover::min_k(&circuit, instances) {
+        Ok(k) => k,
+        Err(err) => {
+            eprintln!("{} {}: skipped, synthesis fails: {:?}", group, label, err);
+            return;
+        }
+    };
+    let id = format!("{}/k={}", label, k);
+
+    let params = prover::setup(k);
+    let params_verifier = prover::verifier_params(&params, public_inputs.len()).unwrap();
+    let pk = prover::keygen(&params, &circuit).unwrap();
+    let proof = prover::prove(&params, &pk, circuit.clone(), instances).unwrap();
+    eprintln!("{} {}: proof size {} bytes", group, id, proof.len());
+
+    let mut group = c.benchmark_group(group);
+    group.sample_size(10);
+    group.bench_function(BenchmarkId::new("keygen", &id), |b| {
+        b.iter(|| {
+            let vk = keygen_vk(&params, &circuit).unwrap();
+            keygen_pk(&params, vk, &circuit).unwrap()
+        })
+    });
+    group.bench_function(BenchmarkId::new("prove", &id), |b| {
+        b.iter(|| prover::prove(&params, &pk, circuit.clone(), instances).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("verify", &id), |b| {
+        b.iter(|| prover::verify(&params_verifier, pk.get_vk(), instances, &proof).unwrap())
+    });
+    group.finish();
+}
+
+fn fibo(c: &mut Criterion) {
+    // `get_fibo_seq` works in u64, which overflows after the 93rd element.
+    for num in [8, 16, 32, 64, 90] {
+        let seq = get_fibo_seq(1, 1, num);
+        let circuit = FiboCircuit {
+            a: Fp::from(seq[0]),
+            b: Fp::from(seq[1]),
+            num,
+        };
+        let label = format!("num={}", num);
+        bench_circuit(c, "fibo", label, circuit, vec![Fp::from(seq[num - 1])]);
+    }
+}
+
+/// The multi-region layout `FiboCircuit` had before it moved onto
+/// `RecurrenceChip`: one region and two copy constraints per step. This is the
+/// baseline `fibo_column` is measured against; `fibo` is already a single
+/// region.
+fn fibo_regions(c: &mut Criterion) {
+    for num in [8, 16, 32, 64, 90] {
+        let seq = get_fibo_seq(1, 1, num);
+        let circuit = FiboRegionsCircuit {
+            a: Fp::from(seq[0]),
+            b: Fp::from(seq[1]),
+            num,
+        };
+        let label = format!("num={}", num);
+        bench_circuit(c, "fibo_regions", label, circuit, vec![Fp::from(seq[num - 1])]);
+    }
+}
+
+/// The same sequences as `fibo_regions` in one advice column, without a copy
+/// constraint per step.
+fn fibo_column(c: &mut Criterion) {
+    for num in [8, 16, 32, 64, 90] {
+        let seq = get_fibo_seq(1, 1, num);
+        let circuit = FiboColumnCircuit {
+            a: Fp::from(seq[0]),
+            b: Fp::from(seq[1]),
+            num,
+        };
+        let label = format!("num={}", num);
+        bench_circuit(c, "fibo_column", label, circuit, vec![Fp::from(seq[num - 1])]);
+    }
+}
+
+fn custom_fibo(c: &mut Criterion) {
+    // Wider chunks mean fewer rows per bitwise operation but tables of
+    // 2^(2 * chunk_bits) rows.
+    for (num, chunk_bits) in [(14, 4), (100, 2), (100, 4)] {
+        let seq = get_sequence(1, 3, 2, num).unwrap();
+        let circuit = CustomFiboCircuit {
+            a: Fp::from(seq[0]),
+            b: Fp::from(seq[1]),
+            c: Fp::from(seq[2]),
+            num,
+            chunk_bits,
+        };
+        let label = format!("num={}/chunk_bits={}", num, chunk_bits);
+        bench_circuit(c, "custom_fibo", label, circuit, vec![Fp::from(seq[num - 1])]);
+    }
+}
+
+fn arithmetic(c: &mut Criterion) {
+    let (a, b, c_, d) = (Fp::from(7), Fp::from(3), Fp::from(5), Fp::from(6));
+    let circuit = MyCircuit {
+        a: Some(a),
+        b: Some(b),
+        c: Some(c_),
+        d: Some(d),
+    };
+    let e = (a - b) + (c_ * d);
+    bench_circuit(c, "arithmetic", "sub_mul".to_string(), circuit, vec![e]);
+}
+
+criterion_group!(benches, fibo, fibo_regions, fibo_column, custom_fibo, arithmetic);
+criterion_main!(benches);
@@ -0,0 +1,147 @@
+//! The Fibonacci layout `FiboCircuit` had before it moved onto
+//! `RecurrenceChip`, kept as the baseline of the `fibo_column` benchmark: one
+//! `add` gate over three advice columns and one region per step, each copying
+//! the previous two elements in.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
+};
+use halo_tutorial::gadgets::Number;
+
+#[derive(Clone, Debug)]
+pub struct FiboRegionsConfig {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub c: Column<Advice>,
+    pub i: Column<Instance>,
+    pub s: Selector,
+}
+
+struct FiboRegionsChip<F: FieldExt> {
+    config: FiboRegionsConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> FiboRegionsChip<F> {
+    fn construct(config: FiboRegionsConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> FiboRegionsConfig {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let c = meta.advice_column();
+        let i = meta.instance_column();
+        let s = meta.selector();
+
+        meta.enable_equality(a);
+        meta.enable_equality(b);
+        meta.enable_equality(c);
+        meta.enable_equality(i);
+
+        meta.create_gate("add", |meta| {
+            let s = meta.query_selector(s);
+            let lhs = meta.query_advice(a, Rotation::cur());
+            let rhs = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(c, Rotation::cur());
+            vec![s * (lhs + rhs - out)]
+        });
+
+        FiboRegionsConfig { a, b, c, i, s }
+    }
+
+    fn load_first_row(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: F,
+        b: F,
+    ) -> Result<(Number<F>, Number<F>), Error> {
+        layouter.assign_region(
+            || "first row",
+            |mut region| {
+                self.config.s.enable(&mut region, 0)?;
+
+                region.assign_advice(|| "a", self.config.a, 0, || Ok(a))?;
+                let b_num = region
+                    .assign_advice(|| "b", self.config.b, 0, || Ok(b))
+                    .map(Number)?;
+                let c_num = region
+                    .assign_advice(|| "c", self.config.c, 0, || Ok(a + b))
+                    .map(Number)?;
+
+                Ok((b_num, c_num))
+            },
+        )
+    }
+
+    fn load_row(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Number<F>,
+        b: &Number<F>,
+    ) -> Result<Number<F>, Error> {
+        layouter.assign_region(
+            || "row",
+            |mut region| {
+                self.config.s.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "a", &mut region, self.config.a, 0)?;
+                b.0.copy_advice(|| "b", &mut region, self.config.b, 0)?;
+                let c = a.0.value().and_then(|a| b.0.value().map(|b| *a + *b));
+
+                region
+                    .assign_advice(|| "c", self.config.c, 0, || c.ok_or(Error::Synthesis))
+                    .map(Number)
+            },
+        )
+    }
+}
+
+/// Proves that the `num`-th element of the sequence starting with `a, b` is
+/// the public input in row 0.
+#[derive(Clone, Debug, Default)]
+pub struct FiboRegionsCircuit<F> {
+    pub a: F,
+    pub b: F,
+    pub num: usize,
+}
+
+impl<F: FieldExt> Circuit<F> for FiboRegionsCircuit<F> {
+    type Config = FiboRegionsConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            num: self.num,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FiboRegionsChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = FiboRegionsChip::construct(config);
+        let (mut b, mut c) =
+            chip.load_first_row(layouter.namespace(|| "first row"), self.a, self.b)?;
+        for _ in 3..self.num {
+            let new_c = chip.load_row(layouter.namespace(|| "row"), &b, &c)?;
+            b = c;
+            c = new_c;
+        }
+        layouter.constrain_instance(c.0.cell(), chip.config.i, 0)
+    }
+}
@@ -0,0 +1,87 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo_tutorial::gadgets::{
+    bits::{BitsChip, BitsConfig, BitsInstructions},
+    field::{FieldChip, FieldConfig, FieldInstructions},
+    standard::{Coefficients, StandardInstructions},
+};
+use pairing::bn256::Fr as Fp;
+
+/// Width of the operands.
+const BITS: usize = 16;
+
+#[derive(Clone, Debug)]
+struct XorConfig {
+    field_config: FieldConfig,
+    bits_config: BitsConfig,
+}
+
+// ANCHOR: circuit
+/// Proves that the public `c` is `a ^ b` for private 16-bit `a` and `b`,
+/// without a lookup table: both operands are split into bits, each pair of
+/// bits is combined as `x + y - 2xy`, and the result bits are recomposed.
+#[derive(Default)]
+struct XorCircuit<F: FieldExt> {
+    a: Option<F>,
+    b: Option<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for XorCircuit<F> {
+    type Config = XorConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        XorConfig {
+            field_config: FieldChip::configure(meta, advice, instance),
+            bits_config: BitsChip::configure(meta, advice),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config.field_config, ());
+        let bits_chip = BitsChip::<F>::construct(config.bits_config, ());
+
+        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+        let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+        let a_bits = bits_chip.to_bits(layouter.namespace(|| "a to bits"), &a, BITS)?;
+        let b_bits = bits_chip.to_bits(layouter.namespace(|| "b to bits"), &b, BITS)?;
+
+        let xor = Coefficients::output(F::one(), F::one(), -F::from(2), F::zero());
+        let c_bits = a_bits
+            .into_iter()
+            .zip(b_bits)
+            .map(|(x, y)| field_chip.combine(layouter.namespace(|| "x ^ y"), x, Some(y), xor))
+            .collect::<Result<Vec<_>, _>>()?;
+        let c = bits_chip.from_bits(layouter.namespace(|| "c from bits"), &c_bits)?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose c"), c, 0)
+    }
+}
+// ANCHOR_END: circuit
+
+fn main() {
+    let k = 8;
+    let (a, b) = (0xbeef, 0x1234);
+    let circuit = XorCircuit {
+        a: Some(Fp::from(a)),
+        b: Some(Fp::from(b)),
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(a ^ b)]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    println!("{:#x} ^ {:#x} = {:#x} verified", a, b, a ^ b);
+}
@@ -0,0 +1,108 @@
+use group::ff::Field;
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo_tutorial::{
+    circuits::arithmetic::add_or_sub_and_mul,
+    gadgets::{
+        field::{FieldChip, FieldConfig, FieldInstructions},
+        is_zero::IsZeroInstructions,
+    },
+};
+use pairing::bn256::Fr as Fp;
+use rand_core::OsRng;
+
+// ANCHOR: circuit
+/// Proves `e = (a ± b) + (c * d)`, adding `b` if the private `flag` is one and
+/// subtracting it if the flag is zero, and exposes whether `e` equals the
+/// public `target`.
+#[derive(Default)]
+struct BranchCircuit<F: FieldExt> {
+    flag: Option<F>,
+    a: Option<F>,
+    b: Option<F>,
+    c: Option<F>,
+    d: Option<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for BranchCircuit<F> {
+    type Config = FieldConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        FieldChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config, ());
+
+        let flag = field_chip.load_private(layouter.namespace(|| "load flag"), self.flag)?;
+        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+        let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+        let c = field_chip.load_private(layouter.namespace(|| "load c"), self.c)?;
+        let d = field_chip.load_private(layouter.namespace(|| "load d"), self.d)?;
+        let target = field_chip.load_instance(layouter.namespace(|| "load target"), 0)?;
+
+        let e = add_or_sub_and_mul(&field_chip, &mut layouter, flag, a, b, c, d)?;
+        let hit = field_chip.is_equal(layouter.namespace(|| "e == target"), e, target)?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose hit"), hit, 1)
+    }
+}
+// ANCHOR_END: circuit
+
+fn main() {
+    let k = 6;
+
+    let rng = OsRng;
+    let a = Fp::random(rng);
+    let b = Fp::random(rng);
+    let c = Fp::random(rng);
+    let d = Fp::random(rng);
+    let circuit = |flag: u64| BranchCircuit {
+        flag: Some(Fp::from(flag)),
+        a: Some(a),
+        b: Some(b),
+        c: Some(c),
+        d: Some(d),
+    };
+    let run = |flag: u64, target: Fp, hit: u64| {
+        let public_inputs = vec![target, Fp::from(hit)];
+        let prover = MockProver::run(k, &circuit(flag), vec![public_inputs]).unwrap();
+        prover.verify().is_ok()
+    };
+
+    let sum = a + b + c * d;
+    let diff = a - b + c * d;
+
+    // The flag picks the branch, and `is_equal` reports whether the result
+    // matches the target.
+    assert!(run(1, sum, 1));
+    assert!(run(0, diff, 1));
+    assert!(run(1, diff, 0));
+    assert!(run(0, sum, 0));
+
+    // The prover cannot lie about the comparison...
+    assert!(!run(1, sum, 0));
+    assert!(!run(1, diff, 1));
+
+    // ...nor use a flag that is not boolean to get a third result.
+    let other = a + Fp::from(3) * b + c * d;
+    assert!(!run(2, other, 1));
+
+    println!("branches verified");
+}
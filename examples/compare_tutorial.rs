@@ -0,0 +1,113 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo_tutorial::{
+    circuits::fibo::get_fibo_seq,
+    gadgets::{
+        compare::{CompareChip, CompareConfig, CompareInstructions},
+        field::{FieldChip, FieldConfig, FieldInstructions},
+        mul::MulInstructions,
+        range::{RangeCheckChip, RangeCheckConfig},
+    },
+};
+use pairing::bn256::Fr as Fp;
+
+/// Every value is compared as a 32-bit number.
+const BITS: usize = 32;
+const LIMB_BITS: usize = 8;
+
+#[derive(Clone, Debug)]
+struct IncreasingConfig {
+    field_config: FieldConfig,
+    range_config: RangeCheckConfig,
+    compare_config: CompareConfig,
+}
+
+// ANCHOR: circuit
+/// Proves that the private `values` are strictly increasing and that the last
+/// one is below the public bound in row 0.
+///
+/// Each comparison yields a boolean; their product is exposed in row 1, so the
+/// verifier accepts only if it is one.
+#[derive(Default)]
+struct IncreasingCircuit<F: FieldExt> {
+    values: Vec<Option<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for IncreasingCircuit<F> {
+    type Config = IncreasingConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![None; self.values.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let table = meta.lookup_table_column();
+
+        let field_config = FieldChip::configure(meta, advice, instance);
+        let range_config = RangeCheckChip::configure(meta, advice[0], table, LIMB_BITS);
+        let compare_config = CompareChip::configure(meta, advice, range_config.clone());
+
+        IncreasingConfig {
+            field_config,
+            range_config,
+            compare_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config.field_config, ());
+        let range_chip = RangeCheckChip::<F>::construct(config.range_config, ());
+        let compare_chip = CompareChip::<F>::construct(config.compare_config, ());
+        range_chip.load_table(layouter.namespace(|| "range table"))?;
+
+        let bound = field_chip.load_instance(layouter.namespace(|| "load bound"), 0)?;
+        let mut values = self
+            .values
+            .iter()
+            .map(|value| field_chip.load_private(layouter.namespace(|| "load value"), *value))
+            .collect::<Result<Vec<_>, _>>()?;
+        values.push(bound);
+
+        let mut all = field_chip.load_constant(layouter.namespace(|| "true"), F::one())?;
+        for pair in values.windows(2) {
+            let lt = compare_chip.lt(
+                layouter.namespace(|| "x_i < x_i+1"),
+                &pair[0],
+                &pair[1],
+                BITS,
+            )?;
+            all = field_chip.mul(layouter.namespace(|| "and"), all, lt)?;
+        }
+
+        field_chip.expose_public(layouter.namespace(|| "expose result"), all, 1)
+    }
+}
+// ANCHOR_END: circuit
+
+fn main() {
+    // Fibonacci numbers from the third one on are strictly increasing.
+    let seq = get_fibo_seq(1, 1, 12);
+    println!("{:?}", seq);
+
+    let k = 9;
+    let circuit = IncreasingCircuit {
+        values: seq[2..].iter().map(|value| Some(Fp::from(*value))).collect(),
+    };
+    let public_inputs = vec![Fp::from(145), Fp::one()];
+    let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    println!("comparisons verified");
+}
@@ -0,0 +1,102 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use group::ff::Field;
+use halo2_proofs::dev::MockProver;
+use halo_tutorial::gadgets::{
+    div::DivInstructions,
+    field::{FieldChip, FieldConfig, FieldInstructions},
+    mul::MulInstructions,
+};
+use pairing::bn256::Fr as Fp;
+use rand_core::OsRng;
+
+// ANCHOR: circuit
+/// Proves `e = a / b`.
+///
+/// `forged_inverse` lets us play a dishonest prover: when it is set, it is used
+/// as the witness for `1 / b` instead of the real inverse.
+#[derive(Default)]
+struct DivCircuit<F: FieldExt> {
+    a: Option<F>,
+    b: Option<F>,
+    forged_inverse: Option<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for DivCircuit<F> {
+    type Config = FieldConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        FieldChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config, ());
+
+        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+        let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+
+        let e = match self.forged_inverse {
+            None => field_chip.div(layouter.namespace(|| "a / b"), a, b)?,
+            Some(forged) => {
+                let b_inv =
+                    field_chip.assign_inverse(layouter.namespace(|| "1 / b"), &b, Some(forged))?;
+                field_chip.mul(layouter.namespace(|| "a * (1 / b)"), a, b_inv)?
+            }
+        };
+
+        field_chip.expose_public(layouter.namespace(|| "expose e"), e, 0)
+    }
+}
+// ANCHOR_END: circuit
+
+fn main() {
+    let k = 5;
+
+    // Prepare the private and public inputs to the circuit!
+    let rng = OsRng;
+    let a = Fp::random(rng);
+    let b = Fp::random(rng);
+    let e = a * b.invert().unwrap();
+
+    // Given the correct public input, our circuit will verify.
+    let circuit = DivCircuit {
+        a: Some(a),
+        b: Some(b),
+        forged_inverse: None,
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![e]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    // An honest prover cannot divide by zero: synthesis stops with an error.
+    let circuit = DivCircuit {
+        a: Some(a),
+        b: Some(Fp::zero()),
+        forged_inverse: None,
+    };
+    assert!(MockProver::run(k, &circuit, vec![vec![Fp::zero()]]).is_err());
+
+    // A dishonest prover claiming some inverse of zero is caught by the
+    // `a * inv = 1` constraint, whatever inverse and result it claims.
+    let circuit = DivCircuit {
+        a: Some(a),
+        b: Some(Fp::zero()),
+        forged_inverse: Some(Fp::random(rng)),
+    };
+    let prover = MockProver::run(k, &circuit, vec![vec![Fp::zero()]]).unwrap();
+    assert!(prover.verify().is_err());
+}
@@ -0,0 +1,50 @@
+use group::ff::Field;
+use halo2_proofs::dev::MockProver;
+use halo_tutorial::expr::{Expr, ExprCircuit};
+use pairing::bn256::Fr as Fp;
+use rand_core::OsRng;
+
+/// Runs `expr` with random private values for `private` and the given public
+/// variables.
+fn check(k: u32, expr: &str, private: &[&str], public: &[(&str, Fp)]) {
+    let rng = OsRng;
+    let circuit = ExprCircuit {
+        expr: Expr::parse(expr).unwrap(),
+        private: private
+            .iter()
+            .map(|name| (name.to_string(), Some(Fp::random(rng))))
+            .collect(),
+        public: public
+            .iter()
+            .map(|(name, value)| (name.to_string(), Some(*value)))
+            .collect(),
+    };
+
+    // The instance column holds the public variables, then the result.
+    let public_inputs = circuit.public_inputs().unwrap();
+    let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    println!("{} verified", expr);
+}
+
+fn main() {
+    // The second and third tutorial circuits, without writing any chip code.
+    check(5, "(a + b) + (c + d)", &["a", "b", "c", "d"], &[]);
+    check(5, "(a - b) + (c * d)", &["a", "b", "c", "d"], &[]);
+
+    // The nine-input sum.
+    check(
+        6,
+        "(a + b + c) + (d + e + f) + (g + h + j)",
+        &["a", "b", "c", "d", "e", "f", "g", "h", "j"],
+        &[],
+    );
+
+    // Public variables are read from the instance column.
+    check(5, "x * x * scale + x", &["x"], &[("scale", Fp::from(3))]);
+
+    // Integer literals are fixed by the verifying key, not by the prover.
+    check(5, "3 * x * x + 2 * x + 7", &["x"], &[]);
+    check(5, "(x - 1) * (y + 1) * 12", &["x", "y"], &[]);
+}
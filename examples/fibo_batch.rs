@@ -0,0 +1,73 @@
+use halo2_proofs::pairing::bn256::Fr as Fp;
+use halo_tutorial::{
+    batch::{check_batch, prove_batch, verify_batch, BatchError, BatchVerifier},
+    circuits::fibo::{get_fibo_seq, FiboCircuit},
+    prover,
+};
+
+fn main() {
+    // Every circuit in a batch shares one key, so they all use the same `num`.
+    let num = 12;
+    let k = 4;
+    let starts = [(1, 1), (2, 1), (3, 5), (8, 13)];
+
+    let circuits: Vec<_> = starts
+        .iter()
+        .map(|&(a, b)| FiboCircuit {
+            a: Fp::from(a),
+            b: Fp::from(b),
+            num,
+        })
+        .collect();
+    let instances: Vec<_> = starts
+        .iter()
+        .map(|&(a, b)| vec![vec![Fp::from(get_fibo_seq(a, b, num)[num - 1])]])
+        .collect();
+
+    // Check each member with MockProver, then fold all of them into one proof
+    // that is verified with a single pairing check.
+    check_batch(k, &circuits, &instances).expect("every member should be satisfied");
+
+    let params = prover::setup(k);
+    let params_verifier = prover::verifier_params(&params, 1).unwrap();
+    let pk = prover::keygen(&params, &circuits[0]).unwrap();
+
+    let proof = prove_batch(&params, &pk, &circuits, &instances).unwrap();
+    verify_batch(&params_verifier, pk.get_vk(), &instances, &proof).expect("batch should verify");
+    println!("batch of {} proven in {} bytes", circuits.len(), proof.len());
+
+    // Every circuit needs its own instance set, so a short list is refused.
+    assert!(check_batch(k, &circuits, &instances[..3]).is_err());
+    assert!(prove_batch(&params, &pk, &circuits[..3], &instances).is_err());
+    assert!(verify_batch(&params_verifier, pk.get_vk(), &instances[..3], &proof).is_err());
+
+    // A wrong public input anywhere rejects the whole batch proof.
+    let mut wrong = instances.clone();
+    wrong[2][0][0] += Fp::one();
+    assert!(verify_batch(&params_verifier, pk.get_vk(), &wrong, &proof).is_err());
+
+    // Separate proofs are checked together with one final pairing check.
+    let proofs: Vec<_> = circuits
+        .into_iter()
+        .zip(&instances)
+        .map(|(circuit, instance)| {
+            let slices: Vec<&[Fp]> = instance.iter().map(|column| &column[..]).collect();
+            prover::prove(&params, &pk, circuit, &slices).unwrap()
+        })
+        .collect();
+    let mut verifier = BatchVerifier::new(&params_verifier, pk.get_vk());
+    for (instance, proof) in instances.iter().zip(&proofs) {
+        verifier.add_proof(instance.clone(), proof.clone());
+    }
+    verifier.finalize().expect("separate proofs should verify");
+
+    // When the folded check fails, the batch verifier names the bad proofs.
+    let mut verifier = BatchVerifier::new(&params_verifier, pk.get_vk());
+    for (instance, proof) in wrong.iter().zip(proofs) {
+        verifier.add_proof(instance.clone(), proof);
+    }
+    match verifier.finalize() {
+        Err(BatchError::Failed(failed)) => assert_eq!(failed, vec![2]),
+        other => panic!("expected proof 2 to fail, got {:?}", other),
+    }
+}
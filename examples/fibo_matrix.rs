@@ -0,0 +1,18 @@
+use halo2_proofs::{dev::MockProver, pairing::bn256::Fr as Fp};
+use halo_tutorial::circuits::fibo_matrix::{fibo_pow, FiboMatrixCircuit};
+
+fn main() {
+    // A large index costs 40 bits, i.e. 80 matrix products, instead of 10^12
+    // rows.
+    let k = 10;
+    let n = 1_000_000_000_000;
+    let circuit = FiboMatrixCircuit {
+        n: Fp::from(n),
+        bits: 40,
+        public_n: true,
+    };
+    let public_inputs = vec![fibo_pow::<Fp>(n), Fp::from(n)];
+    let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    println!("F({}) = {:?} (mod r) verified", n, fibo_pow::<Fp>(n));
+}
@@ -0,0 +1,69 @@
+use halo2_proofs::{dev::MockProver, pairing::bn256::Fr as Fp};
+use halo_tutorial::{
+    circuits,
+    circuits::fibo::get_fibo_seq,
+    circuits::fibo_padded::{FiboPaddedCircuit, CAPACITY},
+    proof_file::ProofFile,
+    store::KeyStore,
+};
+
+fn main() {
+    // The circuit always has room for CAPACITY steps; `n` only changes the
+    // witness and the public inputs.
+    let seq = get_fibo_seq(0, 1, CAPACITY + 1);
+    let circuit = |n: usize| FiboPaddedCircuit {
+        a: Fp::from(seq[0]),
+        b: Fp::from(seq[1]),
+        n,
+        capacity: CAPACITY,
+    };
+    let public_inputs = |x_n: u64, n: usize| vec![Fp::from(x_n), Fp::from(n as u64)];
+    let k = 7;
+
+    for n in [0, 1, 10, CAPACITY] {
+        let prover = MockProver::run(k, &circuit(n), vec![public_inputs(seq[n], n)]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // The claimed element must match the public index.
+        let prover = MockProver::run(k, &circuit(n), vec![public_inputs(seq[n] + 1, n)]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+    let prover = MockProver::run(k, &circuit(10), vec![public_inputs(seq[10], 11)]).unwrap();
+    assert!(prover.verify().is_err());
+
+    // An index beyond the capacity cannot be synthesized.
+    assert!(MockProver::run(k, &circuit(CAPACITY + 1), vec![public_inputs(0, 0)]).is_err());
+
+    // Real proofs for different `n` share one cached key.
+    let name = format!("fibo_padded-c{}", CAPACITY);
+    let store = KeyStore::new("keys");
+    let mut vk_hash = None;
+    for n in [5, 20, CAPACITY] {
+        let instances = public_inputs(seq[n], n);
+        let output = store
+            .run(&name, k, circuit(n), &[&instances])
+            .expect("real proof should verify");
+        println!(
+            "F({}) = {}: proof size {} bytes",
+            n,
+            seq[n],
+            output.proof.len()
+        );
+        if let Some(vk_hash) = &vk_hash {
+            assert_eq!(vk_hash, &output.vk_hash);
+        }
+        vk_hash = Some(output.vk_hash.clone());
+
+        // e.g. cargo run --bin verify keys/fibo_padded-c64-n5.proof keys/fibo_padded-c64-k7.vk keys/params-k7.bin
+        let proof_file = ProofFile {
+            circuit: circuits::FIBO_PADDED.to_string(),
+            k,
+            vk_hash: output.vk_hash,
+            instances: vec![instances],
+            proof: output.proof,
+        };
+        proof_file
+            .save(format!("keys/{}-n{}.proof", name, n))
+            .expect("proof file should be written");
+    }
+}
@@ -0,0 +1,111 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo_tutorial::{
+    gadgets::{
+        dynamic_table::{DynamicTableChip, DynamicTableConfig, DynamicTableInstructions},
+        field::{FieldChip, FieldConfig},
+    },
+    prover,
+};
+use pairing::bn256::Fr as Fp;
+
+/// Largest map a proof can load.
+const CAPACITY: usize = 16;
+
+#[derive(Clone, Debug)]
+struct KvConfig {
+    field_config: FieldConfig,
+    table_config: DynamicTableConfig<2>,
+}
+
+// ANCHOR: circuit
+/// Proves that the public `(key, value)` pairs, in rows `2i` and `2i + 1` of
+/// the instance column, are entries of the private `map`.
+#[derive(Clone)]
+struct KvCircuit<F: FieldExt> {
+    map: Vec<[Option<F>; 2]>,
+    queries: usize,
+}
+
+impl<F: FieldExt> Circuit<F> for KvCircuit<F> {
+    type Config = KvConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            map: vec![[None; 2]; self.map.len()],
+            queries: self.queries,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let field_config = FieldChip::configure(meta, advice, instance);
+        // The map shares its columns with the field chip and the queries; the
+        // tag tells its rows apart.
+        let table_config = DynamicTableChip::configure(meta, advice, advice);
+
+        KvConfig {
+            field_config,
+            table_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config.field_config, ());
+        let table_chip = DynamicTableChip::<F, 2>::construct(config.table_config, ());
+        table_chip.load(layouter.namespace(|| "map"), &self.map, CAPACITY)?;
+
+        for i in 0..self.queries {
+            let key = field_chip.load_instance(layouter.namespace(|| "key"), 2 * i)?;
+            let value = field_chip.load_instance(layouter.namespace(|| "value"), 2 * i + 1)?;
+            table_chip.lookup(layouter.namespace(|| "map[key] = value"), &[key, value])?;
+        }
+        Ok(())
+    }
+}
+// ANCHOR_END: circuit
+
+fn circuit(map: &[(u64, u64)], queries: usize) -> KvCircuit<Fp> {
+    KvCircuit {
+        map: map
+            .iter()
+            .map(|(key, value)| [Some(Fp::from(*key)), Some(Fp::from(*value))])
+            .collect(),
+        queries,
+    }
+}
+
+fn main() {
+    let balances = [(1, 100), (2, 250), (7, 0), (42, 9)];
+    let queries = [(2, 250), (42, 9)];
+    let public_inputs = queries
+        .iter()
+        .flat_map(|(key, value)| [Fp::from(*key), Fp::from(*value)])
+        .collect();
+    let lookups = circuit(&balances, queries.len());
+    let mock_prover = MockProver::run(6, &lookups, vec![public_inputs]).unwrap();
+    assert_eq!(mock_prover.verify(), Ok(()));
+    println!("lookups verified");
+
+    // The map is a witness: maps of different sizes share one key.
+    let params = prover::setup(6);
+    let vk_hash = |map: &[(u64, u64)]| {
+        let pk = prover::keygen(&params, &circuit(map, 1)).expect("keygen should succeed");
+        prover::vk_hash(pk.get_vk())
+    };
+    assert_eq!(vk_hash(&balances), vk_hash(&[(5, 5)]));
+    println!(
+        "one verifying key for every map of up to {} entries",
+        CAPACITY
+    );
+}
@@ -0,0 +1,88 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo_tutorial::gadgets::{
+    field::{FieldChip, FieldConfig, FieldInstructions},
+    mul::MulInstructions,
+    range::{RangeCheckChip, RangeCheckConfig, RangeCheckInstructions},
+};
+use pairing::bn256::Fr as Fp;
+
+/// Width of the limbs looked up in the range table, i.e. a 256-row table.
+const LIMB_BITS: usize = 8;
+
+#[derive(Clone, Debug)]
+struct RangeConfig {
+    field_config: FieldConfig,
+    range_config: RangeCheckConfig,
+}
+
+// ANCHOR: circuit
+/// Proves that the public `c = a * b` is the product of two `bits`-bit numbers.
+#[derive(Default)]
+struct ProductCircuit<F: FieldExt> {
+    a: Option<F>,
+    b: Option<F>,
+    bits: usize,
+}
+
+impl<F: FieldExt> Circuit<F> for ProductCircuit<F> {
+    type Config = RangeConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            bits: self.bits,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let table = meta.lookup_table_column();
+
+        // The range check only needs one advice column; it shares the first
+        // one with FieldChip.
+        RangeConfig {
+            field_config: FieldChip::configure(meta, advice, instance),
+            range_config: RangeCheckChip::configure(meta, advice[0], table, LIMB_BITS),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config.field_config, ());
+        let range_chip = RangeCheckChip::<F>::construct(config.range_config, ());
+        range_chip.load_table(layouter.namespace(|| "range table"))?;
+
+        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+        let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+        range_chip.range_check(layouter.namespace(|| "a < 2^bits"), &a, self.bits)?;
+        range_chip.range_check(layouter.namespace(|| "b < 2^bits"), &b, self.bits)?;
+
+        let c = field_chip.mul(layouter.namespace(|| "a * b"), a, b)?;
+        field_chip.expose_public(layouter.namespace(|| "expose c"), c, 0)
+    }
+}
+// ANCHOR_END: circuit
+
+fn main() {
+    let k = 9;
+    let (a, b) = (1023, 1000);
+    let circuit = ProductCircuit {
+        a: Some(Fp::from(a)),
+        b: Some(Fp::from(b)),
+        bits: 10,
+    };
+    let c = Fp::from(a) * Fp::from(b);
+    let prover = MockProver::run(k, &circuit, vec![vec![c]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    println!("range checks verified");
+}
@@ -0,0 +1,103 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo_tutorial::gadgets::recurrence::{
+    linear_recurrence, RecurrenceChip, RecurrenceCoefficients, RecurrenceConfig,
+    RecurrenceInstructions,
+};
+use pairing::bn256::Fr as Fp;
+
+// ANCHOR: circuit
+/// Proves that the `num`-th element of the order-`K` recurrence starting with
+/// the private `init` is the public input in row 0. Public coefficients are
+/// read from rows `1..=K`.
+#[derive(Clone)]
+struct RecurrenceCircuit<F: FieldExt, const K: usize> {
+    coefficients: RecurrenceCoefficients<F>,
+    init: [Option<F>; K],
+    num: usize,
+}
+
+impl<F: FieldExt, const K: usize> Circuit<F> for RecurrenceCircuit<F, K> {
+    type Config = RecurrenceConfig<K>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            coefficients: self.coefficients.clone(),
+            init: [None; K],
+            num: self.num,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        RecurrenceChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = RecurrenceChip::<F, K>::construct(config, ());
+        let seq = chip.sequence(
+            layouter.namespace(|| "sequence"),
+            &self.coefficients,
+            &self.init,
+            self.num,
+        )?;
+        let last = seq.last().ok_or(Error::Synthesis)?.clone();
+        chip.expose_public(layouter.namespace(|| "expose last"), last, 0)
+    }
+}
+// ANCHOR_END: circuit
+
+/// Checks the recurrence with constant coefficients against the native
+/// generator, and returns the element it proved.
+fn prove_fixed<const K: usize>(coefficients: [u64; K], init: [u64; K], num: usize) -> u64 {
+    let k = 5;
+    let expected = linear_recurrence(&coefficients, &init, num)[num - 1];
+    let circuit = RecurrenceCircuit::<Fp, K> {
+        coefficients: RecurrenceCoefficients::Fixed(coefficients.map(Fp::from).to_vec()),
+        init: init.map(|x| Some(Fp::from(x))),
+        num,
+    };
+
+    let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(expected)]]).unwrap();
+    prover.assert_satisfied();
+    let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(expected + 1)]]).unwrap();
+    assert!(prover.verify().is_err());
+    expected
+}
+
+fn main() {
+    let num = 20;
+    let fibonacci = prove_fixed([1, 1], [0, 1], num);
+    let lucas = prove_fixed([1, 1], [2, 1], num);
+    let pell = prove_fixed([2, 1], [0, 1], num);
+    let tribonacci = prove_fixed([1, 1, 1], [0, 0, 1], num);
+    println!("fibonacci: {}", fibonacci);
+    println!("lucas: {}", lucas);
+    println!("pell: {}", pell);
+    println!("tribonacci: {}", tribonacci);
+
+    // With public coefficients one circuit serves every recurrence of order 2:
+    // the verifier supplies `c_1, c_2` next to the claimed result.
+    let circuit = RecurrenceCircuit::<Fp, 2> {
+        coefficients: RecurrenceCoefficients::Public(1),
+        init: [Some(Fp::from(0)), Some(Fp::from(1))],
+        num,
+    };
+    let public =
+        |result: u64, c: [u64; 2]| vec![vec![Fp::from(result), Fp::from(c[0]), Fp::from(c[1])]];
+    let prover = MockProver::run(5, &circuit, public(pell, [2, 1])).unwrap();
+    prover.assert_satisfied();
+    let prover = MockProver::run(5, &circuit, public(fibonacci, [1, 1])).unwrap();
+    prover.assert_satisfied();
+    let prover = MockProver::run(5, &circuit, public(pell, [1, 1])).unwrap();
+    assert!(prover.verify().is_err());
+    println!("public coefficients verified");
+}
@@ -1,314 +1,32 @@
- use std::marker::PhantomData;
 use halo2_proofs::{
     arithmetic::FieldExt,
-    circuit::{AssignedCell, Chip, Layouter, Region, SimpleFloorPlanner},
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
-    poly::Rotation,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
 };
 use group::ff::Field;
 use halo2_proofs::dev::MockProver;
+use halo_tutorial::gadgets::{
+    add::AddInstructions,
+    field::{FieldChip, FieldConfig, FieldInstructions},
+    Number,
+};
 use pairing::bn256::Fr as Fp;
 use rand_core::OsRng;
 
-// ANCHOR: field-instructions
-/// A variable representing a number.
-#[derive(Clone)]
-struct Number<F: FieldExt>(AssignedCell<F, F>);
-
-trait FieldInstructions<F: FieldExt>: AddInstructions<F> {
-    /// Variable representing a number.
-    type Num;
-
-    /// Loads a number into the circuit as a private input.
-    fn load_private(
-        &self,
-        layouter: impl Layouter<F>,
-        a: Option<F>,
-    ) -> Result<<Self as FieldInstructions<F>>::Num, Error>;
-
-    /// Returns `e = (a + b) + (c + d)`.
-    fn eval_circuit(
-        &self,
-        layouter: &mut impl Layouter<F>,
-        a: <Self as FieldInstructions<F>>::Num,
-        b: <Self as FieldInstructions<F>>::Num,
-        c: <Self as FieldInstructions<F>>::Num,
-        d: <Self as FieldInstructions<F>>::Num,
-    ) -> Result<<Self as FieldInstructions<F>>::Num, Error>;
-
-    /// Exposes a number as a public input to the circuit.
-    fn expose_public(
-        &self,
-        layouter: impl Layouter<F>,
-        num: <Self as FieldInstructions<F>>::Num,
-        row: usize,
-    ) -> Result<(), Error>;
-}
-// ANCHOR_END: field-instructions
-
-// ANCHOR: add-instructions
-trait AddInstructions<F: FieldExt>: Chip<F> {
-    /// Variable representing a number.
-    type Num;
-
-    /// Returns `c = a + b`.
-    fn add(
-        &self,
-        layouter: impl Layouter<F>,
-        a: Self::Num,
-        b: Self::Num,
-    ) -> Result<Self::Num, Error>;
-}
-
-
-// ANCHOR: field-config
-// The top-level config that provides all necessary columns and permutations
-// for the other configs.
-#[derive(Clone, Debug)]
-struct FieldConfig {
-    /// For this chip, we will use two advice columns to implement our instructions.
-    /// These are also the columns through which we communicate with other parts of
-    /// the circuit.
-    advice: [Column<Advice>; 2],
-
-    /// Public inputs
-    instance: Column<Instance>,
-
-    add_config: AddConfig,
- }
-// ANCHOR END: field-config
-
-// ANCHOR: add-config
-#[derive(Clone, Debug)]
-struct AddConfig {
-    advice: [Column<Advice>; 2],
-    s_add: Selector,
-}
-
-
-
-// ANCHOR END: mul-config
-
-// ANCHOR: field-chip
-/// The top-level chip that will implement the `FieldInstructions`.
-struct FieldChip<F: FieldExt> {
-    config: FieldConfig,
-    _marker: PhantomData<F>,
-}
-// ANCHOR_END: field-chip
-
-// ANCHOR: add-chip
-struct AddChip<F: FieldExt> {
-    config: AddConfig,
-    _marker: PhantomData<F>,
-}
-
-
-// ANCHOR_END: mul-chip
-
-// ANCHOR: add-chip-trait-impl
-impl<F: FieldExt> Chip<F> for AddChip<F> {
-    type Config = AddConfig;
-    type Loaded = ();
-
-    fn config(&self) -> &Self::Config {
-        &self.config
-    }
-
-    fn loaded(&self) -> &Self::Loaded {
-        &()
-    }
-}
-
-// ANCHOR: add-chip-impl
-impl<F: FieldExt> AddChip<F> {
-    fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
-        Self {
-            config,
-            _marker: PhantomData,
-        }
-    }
-
-    fn configure(
-        meta: &mut ConstraintSystem<F>,
-        advice: [Column<Advice>; 2],
-    ) -> <Self as Chip<F>>::Config {
-        let s_add = meta.selector();
-        for column in &advice {
-            meta.enable_equality(*column);
-        }
-        // Define our addition gate!
-        meta.create_gate("add", |meta| {
-            let lhs = meta.query_advice(advice[0], Rotation::cur());
-            let rhs = meta.query_advice(advice[1], Rotation::cur());
-            let out = meta.query_advice(advice[0], Rotation::next());
-            let s_add = meta.query_selector(s_add);
-
-            vec![s_add * (lhs + rhs - out)]
-        });
-
-        AddConfig { advice, s_add }
-    }
-}
-// ANCHOR END: add-chip-impl
-
-// ANCHOR: add-instructions-impl
-impl<F: FieldExt> AddInstructions<F> for FieldChip<F> {
-    type Num = Number<F>;
-    fn add(
-        &self,
-        layouter: impl Layouter<F>,
-        a: Self::Num,
-        b: Self::Num,
-    ) -> Result<Self::Num, Error> {
-        let config = self.config().add_config.clone();
-
-        let add_chip = AddChip::<F>::construct(config, ());
-        add_chip.add(layouter, a, b)
-    }
-}
-
-impl<F: FieldExt> AddInstructions<F> for AddChip<F> {
-    type Num = Number<F>;
-
-    fn add(
-        &self,
-        mut layouter: impl Layouter<F>,
-        a: Self::Num,
-        b: Self::Num,
-    ) -> Result<Self::Num, Error> {
-        let config = self.config();
-
-        layouter.assign_region(
-            || "add",
-            |mut region: Region<'_, F>| {
-                // We only want to use a single addition gate in this region,
-                // so we enable it at region offset 0; this means it will constrain
-                // cells at offsets 0 and 1.
-                config.s_add.enable(&mut region, 0)?;
-
-                // The inputs we've been given could be located anywhere in the circuit,
-                // but we can only rely on relative offsets inside this region. So we
-                // assign new cells inside the region and constrain them to have the
-                // same values as the inputs.
-                a.0.copy_advice(|| "lhs", &mut region, config.advice[0], 0)?;
-                b.0.copy_advice(|| "rhs", &mut region, config.advice[1], 0)?;
-
-                // Now we can compute the addition result, which is to be assigned
-                // into the output position.
-                let value = a.0.value().and_then(|a| b.0.value().map(|b| *a + *b));
-
-                // Finally, we do the assignment to the output, returning a
-                // variable to be used in another part of the circuit.
-                region
-                    .assign_advice(
-                        || "lhs + rhs",
-                        config.advice[0],
-                        1,
-                        || value.ok_or(Error::Synthesis),
-                    )
-                    .map(Number)
-            },
-        )
-    }
-}
-// ANCHOR END: add-instructions-impl
-
-// ANCHOR: field-chip-trait-impl
-impl<F: FieldExt> Chip<F> for FieldChip<F> {
-    type Config = FieldConfig;
-    type Loaded = ();
-
-    fn config(&self) -> &Self::Config {
-        &self.config
-    }
-
-    fn loaded(&self) -> &Self::Loaded {
-        &()
-    }
-}
-// ANCHOR_END: field-chip-trait-impl
-
-// ANCHOR: field-chip-impl
-impl<F: FieldExt> FieldChip<F> {
-    fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
-        Self {
-            config,
-            _marker: PhantomData,
-        }
-    }
-
-    fn configure(
-        meta: &mut ConstraintSystem<F>,
-        advice: [Column<Advice>; 2],
-        instance: Column<Instance>,
-    ) -> <Self as Chip<F>>::Config {
-        let add_config = AddChip::configure(meta, advice);
-
-        meta.enable_equality(instance);
-
-        FieldConfig {
-            advice,
-            instance,
-            add_config,
-         }
-    }
-}
-// ANCHOR_END: field-chip-impl
-
-// ANCHOR: field-instructions-impl
-impl<F: FieldExt> FieldInstructions<F> for FieldChip<F> {
-    type Num = Number<F>;
-
-    fn load_private(
-        &self,
-        mut layouter: impl Layouter<F>,
-        value: Option<F>,
-    ) -> Result<<Self as FieldInstructions<F>>::Num, Error> {
-        let config = self.config();
-
-        layouter.assign_region(
-            || "load private",
-            |mut region| {
-                region
-                    .assign_advice(
-                        || "private input",
-                        config.advice[0],
-                        0,
-                        || value.ok_or(Error::Synthesis),
-                    )
-                    .map(Number)
-            },
-        )
-    }
-
-    /// Returns `e = (a  + b)  +   (c +  d)`.
-    fn eval_circuit(
-        &self,
-        layouter: &mut impl Layouter<F>,
-        a: <Self as FieldInstructions<F>>::Num,
-        b: <Self as FieldInstructions<F>>::Num,
-        c: <Self as FieldInstructions<F>>::Num,
-        d: <Self as FieldInstructions<F>>::Num,
-    ) -> Result<<Self as FieldInstructions<F>>::Num, Error> {
-        let ab = self.add(layouter.namespace(|| "a + b"), a, b)?;
-        let cd = self.add(layouter.namespace(|| "c + d"), c,d)?;
-
-        self.add(layouter.namespace(|| "ab + cd"), ab, cd)
-     }
-
-    fn expose_public(
-        &self,
-        mut layouter: impl Layouter<F>,
-        num: <Self as FieldInstructions<F>>::Num,
-        row: usize,
-    ) -> Result<(), Error> {
-        let config = self.config();
-
-        layouter.constrain_instance(num.0.cell(), config.instance, row)
-    }
+/// Returns `e = (a + b) + (c + d)`.
+fn eval_circuit<F: FieldExt>(
+    field_chip: &FieldChip<F>,
+    layouter: &mut impl Layouter<F>,
+    a: Number<F>,
+    b: Number<F>,
+    c: Number<F>,
+    d: Number<F>,
+) -> Result<Number<F>, Error> {
+    let ab = field_chip.add(layouter.namespace(|| "a + b"), a, b)?;
+    let cd = field_chip.add(layouter.namespace(|| "c + d"), c, d)?;
+
+    field_chip.add(layouter.namespace(|| "ab + cd"), ab, cd)
 }
-// ANCHOR_END: field-instructions-impl
 
 // ANCHOR: circuit
 /// The full circuit implementation.
@@ -356,13 +74,14 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
         let c = field_chip.load_private(layouter.namespace(|| "load c"), self.c)?;
         let d = field_chip.load_private(layouter.namespace(|| "load d"), self.d)?;
 
-        // Use `add_and_mul` to get `e = (a + b)  +  c + d`.
-        let e = field_chip.eval_circuit(&mut layouter, a, b, c, d)?;
+        // Use `eval_circuit` to get `e = (a + b) + (c + d)`.
+        let e = eval_circuit(&field_chip, &mut layouter, a, b, c, d)?;
 
         // Expose the result as a public input to the circuit.
-        field_chip.expose_public(layouter.namespace(|| "expose d"), e, 0)
+        field_chip.expose_public(layouter.namespace(|| "expose e"), e, 0)
     }
 }
+// ANCHOR_END: circuit
 
 fn main() {
     // ANCHOR: test-circuit
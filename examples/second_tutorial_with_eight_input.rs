@@ -0,0 +1,103 @@
+use group::ff::Field;
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo_tutorial::gadgets::{
+    add::{AddChip, AddConfig},
+    field::{FieldChip, FieldConfig, FieldInstructions},
+};
+use pairing::bn256::Fr as Fp;
+use rand_core::OsRng;
+
+// ANCHOR: config
+/// `FieldChip` loads the inputs and exposes the result through the first two
+/// advice columns; the eight-input `AddChip` spans all eight.
+#[derive(Clone, Debug)]
+struct MyConfig {
+    field_config: FieldConfig,
+    add8_config: AddConfig<8>,
+}
+// ANCHOR_END: config
+
+// ANCHOR: circuit
+/// Proves that the private inputs sum to the public input in row 0.
+///
+/// `sum_all` adds 8 inputs in its first region and 7 more in every further
+/// one, so the number of inputs must be `1 + 7 * m`.
+#[derive(Default)]
+struct MyCircuit<F: FieldExt> {
+    inputs: Vec<Option<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = MyConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            inputs: vec![None; self.inputs.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); 8].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+
+        let field_config = FieldChip::configure(meta, [advice[0], advice[1]], instance);
+        let add8_config = AddChip::configure(meta, advice);
+
+        MyConfig {
+            field_config,
+            add8_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config.field_config, ());
+        let add8_chip = AddChip::<F, 8>::construct(config.add8_config, ());
+
+        let mut inputs = vec![];
+        for value in self.inputs.iter() {
+            inputs.push(field_chip.load_private(layouter.namespace(|| "load input"), *value)?);
+        }
+
+        let sum = add8_chip.sum_all(layouter.namespace(|| "sum"), inputs)?;
+        field_chip.expose_public(layouter.namespace(|| "expose sum"), sum, 0)
+    }
+}
+// ANCHOR_END: circuit
+
+fn main() {
+    let k = 6;
+    let rng = OsRng;
+
+    // 15 inputs take two regions of the 8-input gate, where `AddChip<F, 2>`
+    // would need fourteen.
+    for len in [8, 15] {
+        let inputs: Vec<Fp> = (0..len).map(|_| Fp::random(rng)).collect();
+        let sum = inputs.iter().fold(Fp::zero(), |acc, input| acc + input);
+        let circuit = MyCircuit {
+            inputs: inputs.into_iter().map(Some).collect(),
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![sum]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        let prover = MockProver::run(k, &circuit, vec![vec![sum + Fp::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // 10 inputs leave the second region two short, which `sum_all` refuses.
+    let circuit = MyCircuit {
+        inputs: vec![Some(Fp::one()); 10],
+    };
+    assert!(MockProver::run(k, &circuit, vec![vec![Fp::from(10)]]).is_err());
+    println!("8-input sums verified");
+}
@@ -1,329 +1,26 @@
- use std::marker::PhantomData;
 use halo2_proofs::{
     arithmetic::FieldExt,
-    circuit::{AssignedCell, Chip, Layouter, Region, SimpleFloorPlanner},
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
-    poly::Rotation,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
 };
 use group::ff::Field;
 use halo2_proofs::dev::MockProver;
+use halo_tutorial::gadgets::{
+    add::{AddChip, AddConfig, SumInstructions},
+    field::{FieldChip, FieldConfig, FieldInstructions},
+};
 use pairing::bn256::Fr as Fp;
 use rand_core::OsRng;
 
-// ANCHOR: field-instructions
-/// A variable representing a number.
-#[derive(Clone)]
-struct Number<F: FieldExt>(AssignedCell<F, F>);
-
-trait FieldInstructions<F: FieldExt>: AddInstructions<F> {
-    /// Variable representing a number.
-    type Num;
-
-    /// Loads a number into the circuit as a private input.
-    fn load_private(
-        &self,
-        layouter: impl Layouter<F>,
-        a: Option<F>,
-    ) -> Result<<Self as FieldInstructions<F>>::Num, Error>;
-
-    /// Returns `k = (a + b + c) + (d + e +f) + (g + h +j)`.
-    fn eval_circuit(
-        &self,
-        layouter: &mut impl Layouter<F>,
-        a: <Self as FieldInstructions<F>>::Num,
-        b: <Self as FieldInstructions<F>>::Num,
-        c: <Self as FieldInstructions<F>>::Num,
-        d: <Self as FieldInstructions<F>>::Num,
-        e: <Self as FieldInstructions<F>>::Num,
-        f: <Self as FieldInstructions<F>>::Num,
-        g: <Self as FieldInstructions<F>>::Num,
-        h: <Self as FieldInstructions<F>>::Num,
-        j: <Self as FieldInstructions<F>>::Num,
-    ) -> Result<<Self as FieldInstructions<F>>::Num, Error>;
-
-    /// Exposes a number as a public input to the circuit.
-    fn expose_public(
-        &self,
-        layouter: impl Layouter<F>,
-        num: <Self as FieldInstructions<F>>::Num,
-        row: usize,
-    ) -> Result<(), Error>;
-}
-// ANCHOR_END: field-instructions
-
-// ANCHOR: add-instructions
-trait AddInstructions<F: FieldExt>: Chip<F> {
-    /// Variable representing a number.
-    type Num;
-
-    /// Returns `d = a + b + c`.
-    fn add(
-        &self,
-        layouter: impl Layouter<F>,
-        a: Self::Num,
-        b: Self::Num,
-        c:Self::Num,
-    ) -> Result<Self::Num, Error>;
-}
-
-
-// ANCHOR: field-config
-// The top-level config that provides all necessary columns and permutations
-// for the other configs.
+// ANCHOR: config
+/// `FieldChip` brings the two advice columns for loading inputs and exposing
+/// the result; the three-input `AddChip` adds a third column next to them.
 #[derive(Clone, Debug)]
-struct FieldConfig {
-    /// For this chip, we will use two advice columns to implement our instructions.
-    /// These are also the columns through which we communicate with other parts of
-    /// the circuit.
-    advice: [Column<Advice>; 3],
-
-    /// Public inputs
-    instance: Column<Instance>,
-
-    add_config: AddConfig,
- }
-// ANCHOR END: field-config
-
-// ANCHOR: add-config
-#[derive(Clone, Debug)]
-struct AddConfig {
-    advice: [Column<Advice>; 3],
-    s_add: Selector,
-}
-
-
-
-// ANCHOR END: mul-config
-
-// ANCHOR: field-chip
-/// The top-level chip that will implement the `FieldInstructions`.
-struct FieldChip<F: FieldExt> {
-    config: FieldConfig,
-    _marker: PhantomData<F>,
-}
-// ANCHOR_END: field-chip
-
-// ANCHOR: add-chip
-struct AddChip<F: FieldExt> {
-    config: AddConfig,
-    _marker: PhantomData<F>,
-}
-
-
-// ANCHOR_END: mul-chip
-
-// ANCHOR: add-chip-trait-impl
-impl<F: FieldExt> Chip<F> for AddChip<F> {
-    type Config = AddConfig;
-    type Loaded = ();
-
-    fn config(&self) -> &Self::Config {
-        &self.config
-    }
-
-    fn loaded(&self) -> &Self::Loaded {
-        &()
-    }
+struct MyConfig {
+    field_config: FieldConfig,
+    add3_config: AddConfig<3>,
 }
-
-// ANCHOR: add-chip-impl
-impl<F: FieldExt> AddChip<F> {
-    fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
-        Self {
-            config,
-            _marker: PhantomData,
-        }
-    }
-
-    fn configure(
-        meta: &mut ConstraintSystem<F>,
-        advice: [Column<Advice>; 3],
-    ) -> <Self as Chip<F>>::Config {
-        let s_add = meta.selector();
-        for column in &advice {
-            meta.enable_equality(*column);
-        }
-        // Define our addition gate!
-        meta.create_gate("add", |meta| {
-            let lhs = meta.query_advice(advice[0], Rotation::cur());
-            let mhs = meta.query_advice(advice[1], Rotation::cur());
-            let rhs = meta.query_advice(advice[2], Rotation::cur());
-            let out = meta.query_advice(advice[0], Rotation::next());
-            let s_add = meta.query_selector(s_add);
-
-            vec![s_add * (lhs + rhs +mhs- out)]
-        });
-
-        AddConfig { advice, s_add }
-    }
-}
-// ANCHOR END: add-chip-impl
-
-// ANCHOR: add-instructions-impl
-impl<F: FieldExt> AddInstructions<F> for FieldChip<F> {
-    type Num = Number<F>;
-    fn add(
-        &self,
-        layouter: impl Layouter<F>,
-        a: Self::Num,
-        b: Self::Num,
-        c: Self::Num,
-    ) -> Result<Self::Num, Error> {
-        let config = self.config().add_config.clone();
-
-        let add_chip = AddChip::<F>::construct(config, ());
-        add_chip.add(layouter, a, b,c)
-    }
-}
-
-impl<F: FieldExt> AddInstructions<F> for AddChip<F> {
-    type Num = Number<F>;
-
-    fn add(
-        &self,
-        mut layouter: impl Layouter<F>,
-        a: Self::Num,
-        b: Self::Num,
-        c: Self::Num,
-    ) -> Result<Self::Num, Error> {
-        let config = self.config();
-
-        layouter.assign_region(
-            || "add",
-            |mut region: Region<'_, F>| {
-                // We only want to use a single addition gate in this region,
-                // so we enable it at region offset 0; this means it will constrain
-                // cells at offsets 0 and 1.
-                config.s_add.enable(&mut region, 0)?;
-
-                // The inputs we've been given could be located anywhere in the circuit,
-                // but we can only rely on relative offsets inside this region. So we
-                // assign new cells inside the region and constrain them to have the
-                // same values as the inputs.
-                a.0.copy_advice(|| "lhs", &mut region, config.advice[0], 0)?;
-                b.0.copy_advice(|| "mhs", &mut region, config.advice[1], 0)?;
-                c.0.copy_advice(|| "rhs", &mut region, config.advice[2], 0)?;
-
-                // Now we can compute the addition result, which is to be assigned
-                // into the output position.
-                let value = a.0.value().and_then(|a| b.0.value().map(|b| c.0.value().map(|c|*a + *b + *c))).unwrap();
-
-                // Finally, we do the assignment to the output, returning a
-                // variable to be used in another part of the circuit.
-                region
-                    .assign_advice(
-                        || "lhs + mhs + rhs",
-                        config.advice[0],
-                        1,
-                        || value.ok_or(Error::Synthesis),
-                    )
-                    .map(Number)
-            },
-        )
-    }
-}
-// ANCHOR END: add-instructions-impl
-
-// ANCHOR: field-chip-trait-impl
-impl<F: FieldExt> Chip<F> for FieldChip<F> {
-    type Config = FieldConfig;
-    type Loaded = ();
-
-    fn config(&self) -> &Self::Config {
-        &self.config
-    }
-
-    fn loaded(&self) -> &Self::Loaded {
-        &()
-    }
-}
-// ANCHOR_END: field-chip-trait-impl
-
-// ANCHOR: field-chip-impl
-impl<F: FieldExt> FieldChip<F> {
-    fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
-        Self {
-            config,
-            _marker: PhantomData,
-        }
-    }
-
-    fn configure(
-        meta: &mut ConstraintSystem<F>,
-        advice: [Column<Advice>; 3],
-        instance: Column<Instance>,
-    ) -> <Self as Chip<F>>::Config {
-        let add_config = AddChip::configure(meta, advice);
-
-        meta.enable_equality(instance);
-
-        FieldConfig {
-            advice,
-            instance,
-            add_config,
-         }
-    }
-}
-// ANCHOR_END: field-chip-impl
-
-// ANCHOR: field-instructions-impl
-impl<F: FieldExt> FieldInstructions<F> for FieldChip<F> {
-    type Num = Number<F>;
-
-    fn load_private(
-        &self,
-        mut layouter: impl Layouter<F>,
-        value: Option<F>,
-    ) -> Result<<Self as FieldInstructions<F>>::Num, Error> {
-        let config = self.config();
-
-        layouter.assign_region(
-            || "load private",
-            |mut region| {
-                region
-                    .assign_advice(
-                        || "private input",
-                        config.advice[0],
-                        0,
-                        || value.ok_or(Error::Synthesis),
-                    )
-                    .map(Number)
-            },
-        )
-    }
-
-    /// Returns `k = (a + b + c) + (d + e +f) + (g + h +j)`.
-    fn eval_circuit(
-        &self,
-        layouter: &mut impl Layouter<F>,
-        a: <Self as FieldInstructions<F>>::Num,
-        b: <Self as FieldInstructions<F>>::Num,
-        c: <Self as FieldInstructions<F>>::Num,
-        d: <Self as FieldInstructions<F>>::Num,
-        e: <Self as FieldInstructions<F>>::Num,
-        f: <Self as FieldInstructions<F>>::Num,
-        g: <Self as FieldInstructions<F>>::Num,
-        h: <Self as FieldInstructions<F>>::Num,
-        j: <Self as FieldInstructions<F>>::Num,
-    ) -> Result<<Self as FieldInstructions<F>>::Num, Error> {
-        let abc = self.add(layouter.namespace(|| "a + b +c"), a, b,c)?;
-        let def = self.add(layouter.namespace(|| "d+e+f"), d,e,f)?;
-        let ghj = self.add(layouter.namespace(|| "g+h+j"), g,h,j)?;
-        self.add(layouter.namespace(|| "abc + def + ghj"), abc, def, ghj)
-     }
-
-    fn expose_public(
-        &self,
-        mut layouter: impl Layouter<F>,
-        num: <Self as FieldInstructions<F>>::Num,
-        row: usize,
-    ) -> Result<(), Error> {
-        let config = self.config();
-
-        layouter.constrain_instance(num.0.cell(), config.instance, row)
-    }
-}
-// ANCHOR_END: field-instructions-impl
+// ANCHOR_END: config
 
 // ANCHOR: circuit
 /// The full circuit implementation.
@@ -333,20 +30,11 @@ impl<F: FieldExt> FieldInstructions<F> for FieldChip<F> {
 /// were `None` we would get an error.
 #[derive(Default)]
 struct MyCircuit<F: FieldExt> {
-    a: Option<F>,
-    b: Option<F>,
-    c: Option<F>,
-    d: Option<F>,
-    e: Option<F>,
-    f: Option<F>,
-    g: Option<F>,
-    h: Option<F>,
-    j: Option<F>,
+    inputs: [Option<F>; 9],
 }
 
 impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
-    // Since we are using a single chip for everything, we can just reuse its config.
-    type Config = FieldConfig;
+    type Config = MyConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
@@ -354,13 +42,16 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        // We create the two advice columns that FieldChip uses for I/O.
-        let advice = [meta.advice_column(),meta.advice_column(), meta.advice_column()];
-
-        // We also need an instance column to store public inputs.
+        let advice = [meta.advice_column(), meta.advice_column(), meta.advice_column()];
         let instance = meta.instance_column();
 
-        FieldChip::configure(meta, advice, instance)
+        let field_config = FieldChip::configure(meta, [advice[0], advice[1]], instance);
+        let add3_config = AddChip::configure(meta, advice);
+
+        MyConfig {
+            field_config,
+            add3_config,
+        }
     }
 
     fn synthesize(
@@ -368,26 +59,27 @@ impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
         config: Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
-        let field_chip = FieldChip::<F>::construct(config, ());
+        let field_chip = FieldChip::<F>::construct(config.field_config, ());
+        let add3_chip = AddChip::<F, 3>::construct(config.add3_config, ());
 
         // Load our private values into the circuit.
-        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
-        let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
-        let c = field_chip.load_private(layouter.namespace(|| "load c"), self.c)?;
-        let d = field_chip.load_private(layouter.namespace(|| "load d"), self.d)?;
-        let e = field_chip.load_private(layouter.namespace(|| "load e"), self.e)?;
-        let f = field_chip.load_private(layouter.namespace(|| "load f"), self.f)?;
-        let g = field_chip.load_private(layouter.namespace(|| "load g"), self.g)?;
-        let h = field_chip.load_private(layouter.namespace(|| "load h"), self.h)?;
-        let j = field_chip.load_private(layouter.namespace(|| "load j"), self.j)?;
+        let mut inputs = vec![];
+        for value in self.inputs.iter() {
+            inputs.push(field_chip.load_private(layouter.namespace(|| "load input"), *value)?);
+        }
+        let [a, b, c, d, e, f, g, h, j]: [_; 9] = inputs.try_into().unwrap();
 
-        // Use `add_and_mul` to get `e = (a + b)  +  c + d`.
-        let e = field_chip.eval_circuit(&mut layouter, a, b, c, d,e,f,g,h,j)?;
+        // `k = (a + b + c) + (d + e + f) + (g + h + j)`, one row per sum.
+        let abc = add3_chip.sum(layouter.namespace(|| "a + b + c"), [a, b, c])?;
+        let def = add3_chip.sum(layouter.namespace(|| "d + e + f"), [d, e, f])?;
+        let ghj = add3_chip.sum(layouter.namespace(|| "g + h + j"), [g, h, j])?;
+        let k = add3_chip.sum(layouter.namespace(|| "abc + def + ghj"), [abc, def, ghj])?;
 
         // Expose the result as a public input to the circuit.
-        field_chip.expose_public(layouter.namespace(|| "expose d"), e, 0)
+        field_chip.expose_public(layouter.namespace(|| "expose k"), k, 0)
     }
 }
+// ANCHOR_END: circuit
 
 fn main() {
     // ANCHOR: test-circuit
@@ -397,32 +89,16 @@ fn main() {
 
     // Prepare the private and public inputs to the circuit!
     let rng = OsRng;
-    let a = Fp::random(rng);
-    let b = Fp::random(rng);
-    let c = Fp::random(rng);
-    let d = Fp::random(rng);
-    let e = Fp::random(rng);
-    let f = Fp::random(rng);
-    let g = Fp::random(rng);
-    let h = Fp::random(rng);
-    let j = Fp::random(rng);
-    let k = (a+b +c) + (d+e+f) + (g+h+j);
+    let inputs = [(); 9].map(|_| Fp::random(rng));
+    let k = inputs.iter().fold(Fp::zero(), |acc, input| acc + input);
 
     // Instantiate the circuit with the private inputs.
     let circuit = MyCircuit {
-        a: Some(a),
-        b: Some(b),
-        c: Some(c),
-        d: Some(d),
-        e: Some(e),
-        f: Some(f),
-        g: Some(g),
-        h: Some(h),
-        j: Some(j),
+        inputs: inputs.map(Some),
     };
 
-    // Arrange the public input. We expose the multiplication result in row 0
-    // of the instance column, so we position it there in our public inputs.
+    // Arrange the public input. We expose the sum in row 0 of the instance
+    // column, so we position it there in our public inputs.
     let mut public_inputs = vec![k];
 
     // Given the correct public input, our circuit will verify.
@@ -0,0 +1,34 @@
+use halo2_proofs::{dev::MockProver, pairing::bn256::Fr as Fp};
+use halo_tutorial::{
+    circuits::{custom_fibo::get_sequence, fibo::get_fibo_seq},
+    spec::{RecurrenceSpec, SpecCircuit},
+};
+
+/// Proves the `num`-th element of the sequence described by `spec`.
+fn check(spec: &str, init: &[u64], num: usize) -> Vec<u64> {
+    let k = 11;
+    let spec = RecurrenceSpec::parse(spec).expect("spec should parse");
+    let seq = spec
+        .generate(init, num)
+        .expect("sequence should fit in u64");
+    let circuit = SpecCircuit::<Fp>::new(spec, init, num);
+
+    let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(seq[num - 1])]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    seq
+}
+
+fn main() {
+    // The sequences of `FiboCircuit` and `CustomFiboCircuit`, without their
+    // chips.
+    assert_eq!(check("x_1 + x_2", &[1, 1], 20), get_fibo_seq(1, 1, 20));
+    assert_eq!(
+        check("x_3 + (x_2 ^ x_1)", &[1, 3, 2], 14),
+        get_sequence(1, 3, 2, 14).unwrap()
+    );
+
+    // A new puzzle using every operation.
+    let seq = check("(x_1 & 255) * 3 + x_2 - 1 ^ 7", &[5, 9], 12);
+    println!("{:?}", seq);
+    println!("specs verified");
+}
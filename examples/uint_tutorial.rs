@@ -0,0 +1,165 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use halo_tutorial::gadgets::{
+    field::{FieldChip, FieldConfig, FieldInstructions},
+    range::{RangeCheckChip, RangeCheckConfig},
+    uint::{UintChip, UintConfig, UintInstructions, U32, U64},
+};
+use pairing::bn256::Fr as Fp;
+
+const LIMB_BITS: usize = 8;
+
+#[derive(Clone, Debug)]
+struct UintCircuitConfig {
+    field_config: FieldConfig,
+    range_config: RangeCheckConfig,
+    uint_config: UintConfig,
+}
+
+fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> UintCircuitConfig {
+    let advice = [meta.advice_column(), meta.advice_column()];
+    let instance = meta.instance_column();
+    let table = meta.lookup_table_column();
+
+    let field_config = FieldChip::configure(meta, advice, instance);
+    let range_config = RangeCheckChip::configure(meta, advice[0], table, LIMB_BITS);
+    let uint_config = UintChip::configure(meta, advice, range_config.clone());
+
+    UintCircuitConfig {
+        field_config,
+        range_config,
+        uint_config,
+    }
+}
+
+// ANCHOR: circuit
+/// Proves that the `num`-th element of the Fibonacci sequence over u64
+/// starting with `a, b` is the public input in row 0. With `checked`, every
+/// addition must not overflow; otherwise they wrap like `wrapping_add`.
+#[derive(Default)]
+struct FiboU64Circuit<F: FieldExt> {
+    a: Option<F>,
+    b: Option<F>,
+    num: usize,
+    checked: bool,
+}
+
+impl<F: FieldExt> Circuit<F> for FiboU64Circuit<F> {
+    type Config = UintCircuitConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            num: self.num,
+            checked: self.checked,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config.field_config, ());
+        let range_chip = RangeCheckChip::<F>::construct(config.range_config, ());
+        let uint_chip = UintChip::<F>::construct(config.uint_config, ());
+        range_chip.load_table(layouter.namespace(|| "range table"))?;
+
+        let mut a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+        let mut b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+        uint_chip.assert_word(layouter.namespace(|| "a is a u64"), &a, U64)?;
+        uint_chip.assert_word(layouter.namespace(|| "b is a u64"), &b, U64)?;
+
+        for _ in 2..self.num {
+            let c = match self.checked {
+                true => uint_chip.checked_add(layouter.namespace(|| "a + b"), &a, &b, U64)?,
+                false => uint_chip.wrapping_add(layouter.namespace(|| "a + b"), &a, &b, U64)?,
+            };
+            a = b;
+            b = c;
+        }
+
+        field_chip.expose_public(layouter.namespace(|| "expose b"), b, 0)
+    }
+}
+
+/// Proves that the full product of the private u32s `a` and `b` is
+/// `hi * 2^32 + lo` for the public `hi` and `lo` in rows 0 and 1.
+#[derive(Default)]
+struct MulU32Circuit<F: FieldExt> {
+    a: Option<F>,
+    b: Option<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for MulU32Circuit<F> {
+    type Config = UintCircuitConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config.field_config, ());
+        let range_chip = RangeCheckChip::<F>::construct(config.range_config, ());
+        let uint_chip = UintChip::<F>::construct(config.uint_config, ());
+        range_chip.load_table(layouter.namespace(|| "range table"))?;
+
+        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+        let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+        uint_chip.assert_word(layouter.namespace(|| "a is a u32"), &a, U32)?;
+        uint_chip.assert_word(layouter.namespace(|| "b is a u32"), &b, U32)?;
+
+        let (hi, lo) = uint_chip.mul_wide(layouter.namespace(|| "a * b"), &a, &b, U32)?;
+        field_chip.expose_public(layouter.namespace(|| "expose hi"), hi, 0)?;
+        field_chip.expose_public(layouter.namespace(|| "expose lo"), lo, 1)
+    }
+}
+// ANCHOR_END: circuit
+
+fn main() {
+    // The same sequence natively, wrapping like the circuit.
+    let num = 100;
+    let mut seq = vec![0u64, 1];
+    for i in 2..num {
+        seq.push(seq[i - 2].wrapping_add(seq[i - 1]));
+    }
+
+    let circuit = FiboU64Circuit {
+        a: Some(Fp::from(0)),
+        b: Some(Fp::from(1)),
+        num,
+        checked: false,
+    };
+    let prover = MockProver::run(11, &circuit, vec![vec![Fp::from(seq[num - 1])]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    println!("wrapping F({}) = {} verified", num - 1, seq[num - 1]);
+
+    let (a, b) = (0xdead_beef_u64, 0xcafe_babe_u64);
+    let product = a * b;
+    let circuit = MulU32Circuit {
+        a: Some(Fp::from(a)),
+        b: Some(Fp::from(b)),
+    };
+    let public_inputs = vec![Fp::from(product >> 32), Fp::from(product & 0xffff_ffff)];
+    let prover = MockProver::run(9, &circuit, vec![public_inputs]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    println!("{:#x} * {:#x} = {:#x} verified", a, b, product);
+}
@@ -0,0 +1,238 @@
+//! Batch proving and verification for many instances of one circuit.
+//!
+//! Proving `FiboCircuit` for many `(a, b)` pairs and checking every proof with
+//! its own `SingleVerifier` costs one final pairing check per proof. halo2 can
+//! instead put any number of circuits that share a proving key into a single
+//! transcript: `create_proof` takes a slice of circuits and one instance set per
+//! circuit, and the multiopen argument folds all of their openings together,
+//! so [`verify_batch`] ends in one pairing check no matter how many circuits
+//! the batch holds.
+//!
+//! A rejected batch proof does not say which member was wrong, because the
+//! members share every commitment. [`check_batch`] runs `MockProver` on each
+//! member before proving, which is where a prover finds a bad witness.
+//!
+//! Independent proofs, made separately under one verifying key, are checked by
+//! a [`BatchVerifier`]. Every proof reduces to a pair of G1 MSMs that must
+//! satisfy one pairing equation; the verifier keeps these pairs instead of
+//! checking them, adds them up with random weights and does a single final
+//! pairing check. A bad proof makes the sum fail except with negligible
+//! probability, and only then are the proofs checked one by one to report the
+//! indices of the bad ones.
+//!
+//! All circuits of a batch must have the same shape, e.g. `FiboCircuit`s with
+//! the same `num`, since they are proven with one key, and each needs exactly
+//! one instance set; a batch with a circuit or instance set too many or too
+//! few fails with `Error::InvalidInstances`.
+
+use ff::Field;
+use halo2_proofs::{
+    dev::MockProver,
+    pairing::bn256::{Bn256, Fr, G1Affine},
+    plonk::{
+        create_proof, verify_proof, Circuit, Error, ProvingKey, SingleVerifier,
+        VerificationStrategy, VerifyingKey,
+    },
+    poly::{
+        commitment::{Params, ParamsVerifier},
+        msm::PairMSM,
+    },
+    transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+};
+use rand_core::OsRng;
+
+use crate::prover;
+
+/// The instance columns of each circuit in a batch, one `Vec` per column.
+pub type BatchInstances = Vec<Vec<Vec<Fr>>>;
+
+/// Returned when a batch does not verify.
+#[derive(Debug)]
+pub enum BatchError {
+    /// The members at these indices do not satisfy the circuit.
+    Failed(Vec<usize>),
+    /// The folded proof was rejected as a whole.
+    Rejected(Error),
+    /// Proving or key handling failed before anything was checked.
+    Halo2(Error),
+}
+
+impl From<Error> for BatchError {
+    fn from(err: Error) -> Self {
+        BatchError::Halo2(err)
+    }
+}
+
+/// Fails with `Error::InvalidInstances` unless there is one instance set per
+/// circuit and at least one of each.
+fn check_len(circuits: usize, instances: usize) -> Result<(), Error> {
+    if circuits == 0 || circuits != instances {
+        return Err(Error::InvalidInstances);
+    }
+    Ok(())
+}
+
+fn as_slices(instances: &[Vec<Vec<Fr>>]) -> Vec<Vec<&[Fr]>> {
+    instances
+        .iter()
+        .map(|columns| columns.iter().map(|column| &column[..]).collect())
+        .collect()
+}
+
+/// Runs `MockProver` on every member of a batch and returns the indices of
+/// the members whose witness or public inputs are wrong.
+pub fn check_batch<C: Circuit<Fr>>(
+    k: u32,
+    circuits: &[C],
+    instances: &[Vec<Vec<Fr>>],
+) -> Result<(), BatchError> {
+    check_len(circuits.len(), instances.len())?;
+    let mut failed = vec![];
+    for (index, (circuit, instance)) in circuits.iter().zip(instances).enumerate() {
+        let prover = MockProver::run(k, circuit, instance.clone())?;
+        if prover.verify().is_err() {
+            failed.push(index);
+        }
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(BatchError::Failed(failed))
+    }
+}
+
+/// Creates one proof covering all `circuits`.
+pub fn prove_batch<C: Circuit<Fr>>(
+    params: &Params<G1Affine>,
+    pk: &ProvingKey<G1Affine>,
+    circuits: &[C],
+    instances: &[Vec<Vec<Fr>>],
+) -> Result<Vec<u8>, Error> {
+    check_len(circuits.len(), instances.len())?;
+    let instances = as_slices(instances);
+    let instances: Vec<&[&[Fr]]> = instances.iter().map(|columns| &columns[..]).collect();
+
+    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+    create_proof(params, pk, circuits, &instances, OsRng, &mut transcript)?;
+    Ok(transcript.finalize())
+}
+
+/// Checks a proof made by [`prove_batch`] with a single final pairing check.
+///
+/// `instances` must hold one instance set per circuit of the proof. A proof
+/// of more circuits leaves bytes unread and is rejected.
+pub fn verify_batch(
+    params_verifier: &ParamsVerifier<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[Vec<Vec<Fr>>],
+    proof: &[u8],
+) -> Result<(), BatchError> {
+    if instances.is_empty() {
+        return Err(BatchError::Rejected(Error::InvalidInstances));
+    }
+    let instances = as_slices(instances);
+    let instances: Vec<&[&[Fr]]> = instances.iter().map(|columns| &columns[..]).collect();
+
+    let strategy = SingleVerifier::new(params_verifier);
+    let mut rest = proof;
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&mut rest);
+    verify_proof(params_verifier, vk, strategy, &instances, &mut transcript)
+        .map_err(BatchError::Rejected)?;
+    if !rest.is_empty() {
+        return Err(BatchError::Rejected(Error::InvalidInstances));
+    }
+    Ok(())
+}
+
+/// A strategy that returns the pairing inputs of a proof instead of checking
+/// them.
+struct Deferred;
+
+impl<'params> VerificationStrategy<'params, Bn256> for Deferred {
+    type Output = PairMSM<G1Affine>;
+
+    fn process(self, msm: PairMSM<G1Affine>) -> Result<Self::Output, Error> {
+        Ok(msm)
+    }
+}
+
+/// Collects separately created proofs of the same verifying key, checks all
+/// of them with one pairing and reports which of them fail.
+pub struct BatchVerifier<'a> {
+    params_verifier: &'a ParamsVerifier<Bn256>,
+    vk: &'a VerifyingKey<G1Affine>,
+    proofs: Vec<(Vec<Vec<Fr>>, Vec<u8>)>,
+}
+
+impl<'a> BatchVerifier<'a> {
+    pub fn new(params_verifier: &'a ParamsVerifier<Bn256>, vk: &'a VerifyingKey<G1Affine>) -> Self {
+        Self {
+            params_verifier,
+            vk,
+            proofs: vec![],
+        }
+    }
+
+    /// Adds a proof and its instance columns, returning its index.
+    pub fn add_proof(&mut self, instances: Vec<Vec<Fr>>, proof: Vec<u8>) -> usize {
+        self.proofs.push((instances, proof));
+        self.proofs.len() - 1
+    }
+
+    /// Verifies every added proof with a single final pairing check. On
+    /// failure, returns the indices of all proofs that were rejected, not only
+    /// the first one.
+    pub fn finalize(self) -> Result<(), BatchError> {
+        // Proofs that cannot even be read fail right away; the others are
+        // folded into `acc` with a random weight each.
+        let mut failed = vec![];
+        let mut acc: Option<PairMSM<G1Affine>> = None;
+        for (index, (instances, proof)) in self.proofs.iter().enumerate() {
+            let instances: Vec<&[Fr]> = instances.iter().map(|column| &column[..]).collect();
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+            match verify_proof(
+                self.params_verifier,
+                self.vk,
+                Deferred,
+                &[&instances[..]],
+                &mut transcript,
+            ) {
+                Ok(mut msm) => {
+                    msm.scale(Fr::random(OsRng));
+                    acc = Some(match acc.take() {
+                        Some(mut acc) => {
+                            acc.add_msm(msm);
+                            acc
+                        }
+                        None => msm,
+                    });
+                }
+                Err(_) => failed.push(index),
+            }
+        }
+
+        let folded_ok = match acc {
+            Some(acc) => SingleVerifier::new(self.params_verifier).process(acc).is_ok(),
+            None => true,
+        };
+        if !folded_ok {
+            // Some proof is bad; find out which ones.
+            for (index, (instances, proof)) in self.proofs.iter().enumerate() {
+                if failed.contains(&index) {
+                    continue;
+                }
+                let instances: Vec<&[Fr]> = instances.iter().map(|column| &column[..]).collect();
+                if prover::verify(self.params_verifier, self.vk, &instances, proof).is_err() {
+                    failed.push(index);
+                }
+            }
+            failed.sort_unstable();
+        }
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(BatchError::Failed(failed))
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Standalone verifier for proof files.
+//!
+//! ```text
+//! verify <proof-file> <vk-file> <params-file>
+//! ```
+//!
+//! Reads a [`ProofFile`], the verifying key it was made with and the KZG
+//! params, then prints `accept` or `reject`. A proof for another `k` than the
+//! params and the verifying key is rejected before any check. The exit code is
+//! 0 for an accepted proof, 1 for a rejected one and 2 if the inputs cannot be
+//! read. No witness is ever built, so this only needs the circuit
+//! configurations, not the prover setup.
+
+use std::{env, fs::File, io::BufReader, process};
+
+use halo2_proofs::{pairing::bn256::G1Affine, poly::commitment::Params};
+use halo_tutorial::{circuits, proof_file::ProofFile, prover, store::to_hex};
+
+fn fail(msg: String) -> ! {
+    eprintln!("error: {}", msg);
+    process::exit(2)
+}
+
+fn reject(msg: String) -> ! {
+    println!("reject: {}", msg);
+    process::exit(1)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 4 {
+        fail(format!("usage: {} <proof-file> <vk-file> <params-file>", args[0]));
+    }
+
+    let proof_file = ProofFile::load(&args[1])
+        .unwrap_or_else(|err| fail(format!("cannot read proof file {}: {}", args[1], err)));
+
+    let params = File::open(&args[3])
+        .and_then(|file| Params::<G1Affine>::read(&mut BufReader::new(file)))
+        .unwrap_or_else(|err| fail(format!("cannot read params {}: {}", args[3], err)));
+
+    let vk = File::open(&args[2])
+        .and_then(|file| circuits::read_vk(&proof_file.circuit, &mut BufReader::new(file), &params))
+        .unwrap_or_else(|err| fail(format!("cannot read verifying key {}: {}", args[2], err)));
+    // The verifying key is read against the params, so its domain has their k.
+    let k = vk.get_domain().k();
+    if k != proof_file.k {
+        reject(format!(
+            "proof is for k = {}, params and verifying key are for k = {}",
+            proof_file.k, k
+        ));
+    }
+
+    let vk_hash = prover::vk_hash(&vk);
+    if vk_hash != proof_file.vk_hash {
+        reject(format!(
+            "proof was made with verifying key {}, got {}",
+            to_hex(&proof_file.vk_hash),
+            to_hex(&vk_hash)
+        ));
+    }
+
+    let instances = proof_file.instance_slices();
+    let params_verifier = prover::verifier_params(&params, prover::public_inputs_size(&instances))
+        .unwrap_or_else(|err| fail(format!("cannot derive verifier params: {:?}", err)));
+
+    match prover::verify(&params_verifier, &vk, &instances, &proof_file.proof) {
+        Ok(()) => println!("accept: {} (k = {})", proof_file.circuit, proof_file.k),
+        Err(err) => reject(format!("{:?}", err)),
+    }
+}
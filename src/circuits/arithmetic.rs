@@ -0,0 +1,114 @@
+//! The arithmetic circuit `e = (a - b) + (c * d)` proven by
+//! `examples/third_tutorial.rs`, built on the shared [`FieldChip`].
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use crate::gadgets::{
+    add::AddInstructions,
+    boolean::BoolInstructions,
+    field::{FieldChip, FieldConfig, FieldInstructions},
+    mul::MulInstructions,
+    sub::SubInstructions,
+    Number,
+};
+
+/// Returns `e = (a - b) + (c * d)`.
+pub fn add_and_mul<F: FieldExt>(
+    field_chip: &FieldChip<F>,
+    layouter: &mut impl Layouter<F>,
+    a: Number<F>,
+    b: Number<F>,
+    c: Number<F>,
+    d: Number<F>,
+) -> Result<Number<F>, Error> {
+    let ab = field_chip.sub(layouter.namespace(|| "a - b"), a, b)?;
+    let cd = field_chip.mul(layouter.namespace(|| "c * d"), c, d)?;
+
+    field_chip.add(layouter.namespace(|| "(a-b) + (c*d)"), ab, cd)
+}
+
+/// Returns `e = (a + b) + (c * d)` if `flag` is one and `e = (a - b) + (c * d)`
+/// if it is zero. `flag` is constrained to be boolean.
+pub fn add_or_sub_and_mul<F: FieldExt>(
+    field_chip: &FieldChip<F>,
+    layouter: &mut impl Layouter<F>,
+    flag: Number<F>,
+    a: Number<F>,
+    b: Number<F>,
+    c: Number<F>,
+    d: Number<F>,
+) -> Result<Number<F>, Error> {
+    field_chip.assert_bool(layouter.namespace(|| "flag is boolean"), &flag)?;
+
+    // Both branches are computed; the flag only picks one of them.
+    let sum = field_chip.add(layouter.namespace(|| "a + b"), a.clone(), b.clone())?;
+    let diff = field_chip.sub(layouter.namespace(|| "a - b"), a, b)?;
+    let ab = field_chip.select(
+        layouter.namespace(|| "flag ? a + b : a - b"),
+        flag,
+        sum,
+        diff,
+    )?;
+    let cd = field_chip.mul(layouter.namespace(|| "c * d"), c, d)?;
+
+    field_chip.add(layouter.namespace(|| "(a±b) + (c*d)"), ab, cd)
+}
+
+// ANCHOR: circuit
+/// The full circuit implementation.
+///
+/// In this struct we store the private input variables. We use `Option<F>` because
+/// they won't have any value during key generation. During proving, if any of these
+/// were `None` we would get an error.
+#[derive(Clone, Debug, Default)]
+pub struct MyCircuit<F: FieldExt> {
+    pub a: Option<F>,
+    pub b: Option<F>,
+    pub c: Option<F>,
+    pub d: Option<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    // Since we are using a single chip for everything, we can just reuse its config.
+    type Config = FieldConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        // We create the two advice columns that FieldChip uses for I/O.
+        let advice = [meta.advice_column(), meta.advice_column()];
+
+        // We also need an instance column to store public inputs.
+        let instance = meta.instance_column();
+
+        FieldChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config, ());
+
+        // Load our private values into the circuit.
+        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+        let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+        let c = field_chip.load_private(layouter.namespace(|| "load c"), self.c)?;
+        let d = field_chip.load_private(layouter.namespace(|| "load d"), self.d)?;
+
+        // Use `add_and_mul` to get `e = (a - b) + (c * d)`.
+        let e = add_and_mul(&field_chip, &mut layouter, a, b, c, d)?;
+
+        // Expose the result as a public input to the circuit.
+        field_chip.expose_public(layouter.namespace(|| "expose e"), e, 0)
+    }
+}
+// ANCHOR_END: circuit
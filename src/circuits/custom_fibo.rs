@@ -0,0 +1,320 @@
+//! The custom sequence `x_n = x_{n-3} + (x_{n-2} ^ x_{n-1})` proven by
+//! `examples/customFibo.rs`: an `add` gate plus bitwise lookups over u64 words.
+//!
+//! The bitwise operations are the chunked lookups of a
+//! [`BitwiseChip`](crate::gadgets::bitwise::BitwiseChip), which shares the
+//! three advice columns with the `add` gate. The table is a
+//! [`BitwiseTableChip`] shared through `Chip::Loaded`, and its operand width is
+//! the chunk width.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, SimpleFloorPlanner},
+    plonk::*,
+    poly::Rotation,
+};
+
+use crate::gadgets::{
+    bitwise::{
+        BitwiseChip, BitwiseConfig, BitwiseOp, BitwiseTable, BitwiseTableChip, BitwiseTableConfig,
+    },
+    Number,
+};
+
+pub use crate::gadgets::bitwise::CHUNK_BITS;
+
+#[derive(Debug, Clone)]
+pub struct CustomFiboConfig {
+    pub advice: [Column<Advice>; 3],
+    pub instance: Column<Instance>,
+    pub s_add: Selector,
+    pub bitwise_config: BitwiseConfig,
+}
+
+pub struct CustomFiboChip<F: FieldExt> {
+    config: CustomFiboConfig,
+    table: BitwiseTable,
+    _marker: PhantomData<F>,
+}
+
+// ANCHOR: chip-impl
+impl<F: FieldExt> Chip<F> for CustomFiboChip<F> {
+    type Config = CustomFiboConfig;
+    type Loaded = BitwiseTable;
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &self.table
+    }
+}
+// ANCHOR_END: chip-impl
+
+impl<F: FieldExt> CustomFiboChip<F> {
+    /// `table` is what [`BitwiseTableChip::load`] filled `config.table` with;
+    /// its width is the chunk width and must divide 64.
+    pub fn construct(config: CustomFiboConfig, table: BitwiseTable) -> Self {
+        Self {
+            config,
+            table,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        selector: [Selector; 2],
+        table: BitwiseTableConfig,
+    ) -> CustomFiboConfig {
+        let s_add = selector[0];
+        let s_bitwise = selector[1];
+
+        let i = meta.instance_column();
+        meta.enable_equality(i);
+
+        let bitwise_config = BitwiseChip::configure(meta, advice, s_bitwise, table);
+
+        meta.create_gate("add", |meta| {
+            let s_add = meta.query_selector(s_add);
+            let lhs = meta.query_advice(advice[0], Rotation::cur());
+            let rhs = meta.query_advice(advice[1], Rotation::cur());
+            let out = meta.query_advice(advice[2], Rotation::cur());
+            vec![s_add * (lhs + rhs - out)]
+        });
+
+        CustomFiboConfig {
+            advice,
+            instance: i,
+            s_add,
+            bitwise_config,
+        }
+    }
+
+    pub fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: F,
+        b: F,
+        c: F,
+    ) -> Result<(Number<F>, Number<F>, Number<F>), Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "private",
+            |mut region| {
+                let a_num = region.assign_advice(
+                    || "a",
+                    config.advice[0],
+                    0,
+                    || Ok(a),
+                ).map(Number)?;
+
+                let b_num = region.assign_advice(
+                    || "b",
+                    config.advice[1],
+                    0,
+                    || Ok(b),
+                ).map(Number)?;
+
+                let c_num = region.assign_advice(
+                    || "c",
+                    config.advice[2],
+                    0,
+                    || Ok(c),
+                ).map(Number)?;
+
+                Ok((a_num, b_num, c_num))
+            },
+        )
+    }
+
+    pub fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Number<F>,
+        b: &Number<F>,
+    ) -> Result<Number<F>, Error> {
+        let config = self.config();
+        layouter.assign_region(
+            || "add",
+            |mut region| {
+                config.s_add.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "lhs", &mut region, config.advice[0], 0)?;
+                b.0.copy_advice(|| "rhs", &mut region, config.advice[1], 0)?;
+
+                let value = a.0.value().and_then(|a| b.0.value().map(|b| *a + *b));
+
+                region.assign_advice(
+                    || "out",
+                    config.advice[2],
+                    0,
+                    || value.ok_or(Error::Synthesis),
+                ).map(Number)
+            },
+        )
+    }
+
+    fn bitwise_chip(&self) -> BitwiseChip<F> {
+        BitwiseChip::construct(self.config.bitwise_config.clone(), self.table.clone())
+    }
+
+    /// Returns `op(a, b)` through the chunked lookups of [`BitwiseChip`].
+    pub fn bitwise(
+        &self,
+        layouter: impl Layouter<F>,
+        op: BitwiseOp,
+        a: &Number<F>,
+        b: &Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.bitwise_chip().bitwise(layouter, op, a, b)
+    }
+
+    pub fn xor(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Number<F>,
+        b: &Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.bitwise(layouter, BitwiseOp::Xor, a, b)
+    }
+
+    pub fn and(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Number<F>,
+        b: &Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.bitwise(layouter, BitwiseOp::And, a, b)
+    }
+
+    pub fn or(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Number<F>,
+        b: &Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.bitwise(layouter, BitwiseOp::Or, a, b)
+    }
+
+    pub fn not(&self, layouter: impl Layouter<F>, a: &Number<F>) -> Result<Number<F>, Error> {
+        self.bitwise(layouter, BitwiseOp::Not, a, a)
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        num: Number<F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(num.0.cell(), self.config.instance, row)
+    }
+}
+
+/// Proves that the `num`-th element of the sequence starting with `a, b, c` is
+/// the public input in row 0.
+///
+/// Every element must fit in a u64, i.e. [`get_sequence`] must return `Some`
+/// for the same starting values and `num`.
+#[derive(Clone, Debug)]
+pub struct CustomFiboCircuit<F> {
+    pub a: F,
+    pub b: F,
+    pub c: F,
+    pub num: usize,
+    /// Chunk width of the bitwise table; must divide 64.
+    pub chunk_bits: usize,
+}
+
+impl<F: Default> Default for CustomFiboCircuit<F> {
+    fn default() -> Self {
+        Self {
+            a: F::default(),
+            b: F::default(),
+            c: F::default(),
+            num: 0,
+            chunk_bits: CHUNK_BITS,
+        }
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for CustomFiboCircuit<F> {
+    type Config = CustomFiboConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            num: self.num,
+            chunk_bits: self.chunk_bits,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let selector = [meta.selector(), meta.complex_selector()];
+        let table = BitwiseTableChip::configure(meta);
+        CustomFiboChip::configure(meta, advice, selector, table)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>
+    ) -> Result<(), Error> {
+        // The sequence only needs XOR, so the table holds nothing else.
+        let table = BitwiseTableChip::construct(config.bitwise_config.table.clone(), ()).load(
+            layouter.namespace(|| "bitwise table"),
+            &[BitwiseOp::Xor],
+            self.chunk_bits,
+        )?;
+        let chip = CustomFiboChip::construct(config, table);
+        let (mut a, mut b, mut c) = chip.load_private(
+            layouter.namespace(|| "first row"),
+            self.a,
+            self.b,
+            self.c,
+        )?;
+        for _ in 3..self.num {
+            let xor = chip.xor(
+                layouter.namespace(|| "xor"),
+                &b,
+                &c,
+            )?;
+            let new_c = chip.add(
+                layouter.namespace(|| "add"),
+                &a,
+                &xor,
+            )?;
+            a = b;
+            b = c;
+            c = new_c;
+        }
+        chip.expose_public(layouter.namespace(|| "expose c"), c, 0)?;
+        Ok(())
+    }
+}
+
+/// Returns the first `num` elements of the sequence starting with `a, b, c`,
+/// or `None` if one of them does not fit in a u64. The circuit cannot prove
+/// such an element either: its XOR lookups reject operands of 2^64 and more.
+///
+/// Starting with `1, 3, 2` as `examples/customFibo.rs` does, the 157th element
+/// is the first one past `u64::MAX`.
+pub fn get_sequence(a: u64, b: u64, c: u64, num: usize) -> Option<Vec<u64>> {
+    let mut seq = vec![a, b, c];
+    for i in 3..num {
+        seq.push(seq[i - 3].checked_add(seq[i - 2] ^ seq[i - 1])?);
+    }
+    seq.truncate(num);
+    Some(seq)
+}
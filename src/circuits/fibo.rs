@@ -0,0 +1,60 @@
+//! The Fibonacci circuit proven by `examples/fibo1.rs`: a
+//! [`RecurrenceChip`] of order 2 with the constant coefficients `[1, 1]`.
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use crate::gadgets::recurrence::{
+    linear_recurrence, RecurrenceChip, RecurrenceCoefficients, RecurrenceConfig,
+    RecurrenceInstructions,
+};
+
+/// Proves that the `num`-th element of the sequence starting with `a, b` is the
+/// public input in row 0.
+#[derive(Clone, Debug, Default)]
+pub struct FiboCircuit<F> {
+    pub a: F,
+    pub b: F,
+    pub num: usize,
+}
+
+impl<F: FieldExt> Circuit<F> for FiboCircuit<F> {
+    type Config = RecurrenceConfig<2>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            num: self.num,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        RecurrenceChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>
+    ) -> Result<(), Error> {
+        let chip = RecurrenceChip::<F, 2>::construct(config, ());
+        let seq = chip.sequence(
+            layouter.namespace(|| "fibonacci"),
+            &RecurrenceCoefficients::Fixed(vec![F::one(), F::one()]),
+            &[Some(self.a), Some(self.b)],
+            self.num,
+        )?;
+        let c = seq.last().ok_or(Error::Synthesis)?.clone();
+        chip.expose_public(layouter.namespace(|| "expose c"), c, 0)?;
+        Ok(())
+    }
+}
+
+/// Returns the first `num` elements of the sequence starting with `a, b`.
+pub fn get_fibo_seq(a: u64, b: u64, num: usize) -> Vec<u64> {
+    linear_recurrence(&[1, 1], &[a, b], num)
+}
@@ -0,0 +1,168 @@
+//! The Fibonacci sequence in a single advice column.
+//!
+//! [`FiboCircuit`](super::fibo::FiboCircuit) spreads every step over a row of
+//! five advice columns. Here the whole sequence is one region in one column,
+//! and the gate reaches the next two elements with rotations:
+//!
+//! | x       | s |
+//! |---------|---|
+//! | x_0     | 1 |
+//! | x_1     | 1 |
+//! | ...     | 1 |
+//! | x_{n-3} | 1 |
+//! | x_{n-2} |   |
+//! | x_{n-1} |   |
+//!
+//! `s` checks `x[i] + x[i+1] = x[i+2]`, so the only copy constraint left is the
+//! one exposing the result.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, Region, SimpleFloorPlanner},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::gadgets::Number;
+
+#[derive(Clone, Debug)]
+pub struct FiboColumnConfig {
+    pub x: Column<Advice>,
+    pub instance: Column<Instance>,
+    pub s: Selector,
+}
+
+pub struct FiboColumnChip<F: FieldExt> {
+    config: FiboColumnConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for FiboColumnChip<F> {
+    type Config = FiboColumnConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> FiboColumnChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> <Self as Chip<F>>::Config {
+        let x = meta.advice_column();
+        let instance = meta.instance_column();
+        let s = meta.selector();
+        meta.enable_equality(x);
+        meta.enable_equality(instance);
+
+        meta.create_gate("fibo column", |meta| {
+            let s = meta.query_selector(s);
+            let x0 = meta.query_advice(x, Rotation::cur());
+            let x1 = meta.query_advice(x, Rotation::next());
+            let x2 = meta.query_advice(x, Rotation(2));
+            vec![s * (x0 + x1 - x2)]
+        });
+
+        FiboColumnConfig { x, instance, s }
+    }
+
+    /// Lays out the first `num` elements of the sequence starting with `a, b`
+    /// and returns the last one.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Option<F>,
+        b: Option<F>,
+        num: usize,
+    ) -> Result<Number<F>, Error> {
+        if num < 2 {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+        let mut values = vec![a, b];
+        for i in 2..num {
+            values.push(values[i - 2].and_then(|x| values[i - 1].map(|y| x + y)));
+        }
+
+        layouter.assign_region(
+            || "fibo column",
+            |mut region: Region<'_, F>| {
+                let mut last = None;
+                for (row, value) in values.iter().enumerate() {
+                    if row + 2 < num {
+                        config.s.enable(&mut region, row)?;
+                    }
+                    let cell = region.assign_advice(
+                        || format!("x_{}", row),
+                        config.x,
+                        row,
+                        || value.ok_or(Error::Synthesis),
+                    )?;
+                    last = Some(Number(cell));
+                }
+                last.ok_or(Error::Synthesis)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        num: Number<F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(num.0.cell(), self.config().instance, row)
+    }
+}
+
+/// The same statement as [`FiboCircuit`](super::fibo::FiboCircuit) in the
+/// single-column layout.
+#[derive(Clone, Debug, Default)]
+pub struct FiboColumnCircuit<F> {
+    pub a: F,
+    pub b: F,
+    pub num: usize,
+}
+
+impl<F: FieldExt> Circuit<F> for FiboColumnCircuit<F> {
+    type Config = FiboColumnConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            num: self.num,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FiboColumnChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = FiboColumnChip::construct(config, ());
+        let c = chip.assign(
+            layouter.namespace(|| "sequence"),
+            Some(self.a),
+            Some(self.b),
+            self.num,
+        )?;
+        chip.expose_public(layouter.namespace(|| "expose c"), c, 0)
+    }
+}
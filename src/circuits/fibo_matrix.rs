@@ -0,0 +1,161 @@
+//! `F(n)` in logarithmic depth, from powers of the Fibonacci Q-matrix:
+//!
+//! ```text
+//! Q = | 1 1 |    Q^n = | F(n+1) F(n)   |
+//!     | 1 0 |          | F(n)   F(n-1) |
+//! ```
+//!
+//! `n` is split into bits with [`BitsChip`], and `Q^n` is computed by square
+//! and multiply with [`MatrixChip`], most significant bit first. For each bit
+//! `b`, `M` becomes `M * M * S` with `S = Q` if `b = 1` and `S = I` otherwise,
+//! i.e. `S = [[1, b], [b, 1 - b]]`, so every bit costs two matrix products
+//! whatever its value.
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use crate::gadgets::{
+    bits::{BitsChip, BitsConfig, BitsInstructions},
+    boolean::BoolInstructions,
+    field::{FieldChip, FieldConfig, FieldInstructions},
+    matrix::{MatrixChip, MatrixConfig, MatrixInstructions},
+};
+
+#[derive(Clone, Debug)]
+pub struct FiboMatrixConfig {
+    pub field_config: FieldConfig,
+    pub bits_config: BitsConfig,
+    pub matrix_config: MatrixConfig,
+}
+
+/// Proves that `F(n)` of the sequence starting with `0, 1` is the public input
+/// in row 0, for any `n` below `2^bits`. With `public_n`, `n` is read from
+/// row 1 instead of being a private input.
+#[derive(Clone, Debug, Default)]
+pub struct FiboMatrixCircuit<F> {
+    pub n: F,
+    pub bits: usize,
+    pub public_n: bool,
+}
+
+impl<F: FieldExt> Circuit<F> for FiboMatrixCircuit<F> {
+    type Config = FiboMatrixConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            bits: self.bits,
+            public_n: self.public_n,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let instance = meta.instance_column();
+
+        FiboMatrixConfig {
+            field_config: FieldChip::configure(meta, [advice[0], advice[1]], instance),
+            bits_config: BitsChip::configure(meta, [advice[0], advice[1]]),
+            matrix_config: MatrixChip::configure(meta, advice),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config.field_config, ());
+        let bits_chip = BitsChip::<F>::construct(config.bits_config, ());
+        let matrix_chip = MatrixChip::<F>::construct(config.matrix_config, ());
+
+        let n = match self.public_n {
+            true => field_chip.load_instance(layouter.namespace(|| "load n"), 1)?,
+            false => field_chip.load_private(layouter.namespace(|| "load n"), Some(self.n))?,
+        };
+        let bits = bits_chip.to_bits(layouter.namespace(|| "n to bits"), &n, self.bits)?;
+
+        let one = field_chip.load_constant(layouter.namespace(|| "one"), F::one())?;
+        let zero = field_chip.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+        let mut m = [[one.clone(), zero.clone()], [zero, one.clone()]];
+        for bit in bits.into_iter().rev() {
+            m = matrix_chip.mul(layouter.namespace(|| "square"), &m, &m)?;
+            let not_bit = field_chip.not(layouter.namespace(|| "1 - bit"), bit.clone())?;
+            let step = [[one.clone(), bit.clone()], [bit, not_bit]];
+            m = matrix_chip.mul(layouter.namespace(|| "multiply"), &m, &step)?;
+        }
+
+        let [[_, f_n], _] = m;
+        field_chip.expose_public(layouter.namespace(|| "expose F(n)"), f_n, 0)
+    }
+}
+
+/// Returns `F(n)` in the field by the same square and multiply, for indices
+/// whose Fibonacci numbers overflow a u64.
+pub fn fibo_pow<F: FieldExt>(n: u64) -> F {
+    let mul = |a: [F; 4], b: [F; 4]| {
+        [
+            a[0] * b[0] + a[1] * b[2],
+            a[0] * b[1] + a[1] * b[3],
+            a[2] * b[0] + a[3] * b[2],
+            a[2] * b[1] + a[3] * b[3],
+        ]
+    };
+    let q = [F::one(), F::one(), F::one(), F::zero()];
+    let mut m = [F::one(), F::zero(), F::zero(), F::one()];
+    for i in (0..64).rev() {
+        m = mul(m, m);
+        if (n >> i) & 1 == 1 {
+            m = mul(m, q);
+        }
+    }
+    m[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::pairing::bn256::Fr as Fp;
+
+    use super::*;
+    use crate::{circuits::fibo::get_fibo_seq, gadgets::test_utils::mock_verifies};
+
+    fn verifies(n: u64, bits: usize, public_n: bool, claimed: Fp) -> bool {
+        let circuit = FiboMatrixCircuit {
+            n: Fp::from(n),
+            bits,
+            public_n,
+        };
+        let mut public_inputs = vec![claimed];
+        if public_n {
+            public_inputs.push(Fp::from(n));
+        }
+        mock_verifies(10, &circuit, vec![public_inputs])
+    }
+
+    #[test]
+    fn matches_the_linear_sequence() {
+        let seq = get_fibo_seq(0, 1, 93);
+        for n in [0, 1, 2, 3, 10, 50, 92] {
+            let f_n = Fp::from(seq[n]);
+            assert_eq!(fibo_pow::<Fp>(n as u64), f_n);
+            assert!(verifies(n as u64, 7, false, f_n));
+            assert!(verifies(n as u64, 7, true, f_n));
+            assert!(!verifies(n as u64, 7, false, f_n + Fp::from(1)));
+        }
+    }
+
+    #[test]
+    fn index_too_wide() {
+        // 128 does not fit in 7 bits.
+        assert!(!verifies(128, 7, true, fibo_pow(128)));
+    }
+}
@@ -0,0 +1,247 @@
+//! The Fibonacci sequence with a public index in a circuit of fixed capacity.
+//!
+//! [`FiboCircuit`](super::fibo::FiboCircuit) lays out exactly `num` elements,
+//! so every length has its own keys. This circuit always lays out `capacity`
+//! steps, and a step-enable column `e` decides which of them advance the
+//! sequence; the others hold it. A counter sums `e`, so the last row holds
+//! `x_n` and `n`, and both are public:
+//!
+//! | a       | b       | e | cnt | s_step |
+//! |---------|---------|---|-----|--------|
+//! | x_0     | x_1     | 1 | 0   | 1      |
+//! | x_1     | x_2     | 1 | 1   | 1      |
+//! | ...     | ...     | 1 | ... | 1      |
+//! | x_n     | x_{n+1} | 0 | n   | 1      |
+//! | x_n     | x_{n+1} | 0 | n   | 1      |
+//! | x_n     | x_{n+1} |   | n   |        |
+//!
+//! The last row is row `capacity`. Since `s_step` constrains
+//! `a' = a + e·(b - a)`, `b' = b + e·a` and `cnt' = cnt + e` with boolean
+//! `e`, the sequence advances exactly `cnt` times whatever the order of the
+//! enabled steps, so one verifying key serves every `n <= capacity`. Larger
+//! `n` cannot be reached by `capacity` booleans.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, Region, SimpleFloorPlanner},
+    plonk::{
+        Advice, Circuit, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector,
+    },
+    poly::Rotation,
+};
+
+use crate::gadgets::Number;
+
+/// The capacity used by `examples/fibo_padded.rs`. Elements must fit in a u64
+/// for the native sequence, which holds up to `x_92` starting with `0, 1`.
+pub const CAPACITY: usize = 64;
+
+#[derive(Clone, Debug)]
+pub struct FiboPaddedConfig {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    /// The step-enable flags.
+    pub e: Column<Advice>,
+    /// The number of enabled steps so far.
+    pub cnt: Column<Advice>,
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+    pub s_step: Selector,
+}
+
+pub struct FiboPaddedChip<F: FieldExt> {
+    config: FiboPaddedConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for FiboPaddedChip<F> {
+    type Config = FiboPaddedConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> FiboPaddedChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> <Self as Chip<F>>::Config {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let e = meta.advice_column();
+        let cnt = meta.advice_column();
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        let s_step = meta.selector();
+
+        meta.enable_equality(a);
+        meta.enable_equality(cnt);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("padded step", |meta| {
+            let s = meta.query_selector(s_step);
+            let a_cur = meta.query_advice(a, Rotation::cur());
+            let b_cur = meta.query_advice(b, Rotation::cur());
+            let e = meta.query_advice(e, Rotation::cur());
+            let cnt_cur = meta.query_advice(cnt, Rotation::cur());
+            let a_next = meta.query_advice(a, Rotation::next());
+            let b_next = meta.query_advice(b, Rotation::next());
+            let cnt_next = meta.query_advice(cnt, Rotation::next());
+
+            let one = Expression::Constant(F::one());
+            vec![
+                s.clone() * e.clone() * (one - e.clone()),
+                s.clone() * (a_next - a_cur.clone() - e.clone() * (b_cur.clone() - a_cur.clone())),
+                s.clone() * (b_next - b_cur - e.clone() * a_cur),
+                s * (cnt_next - cnt_cur - e),
+            ]
+        });
+
+        FiboPaddedConfig {
+            a,
+            b,
+            e,
+            cnt,
+            instance,
+            constant,
+            s_step,
+        }
+    }
+
+    /// Lays out `capacity` steps of the sequence starting with `a, b`, of
+    /// which the first `n` are enabled. Returns `x_n` and `n`.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Option<F>,
+        b: Option<F>,
+        n: Option<usize>,
+        capacity: usize,
+    ) -> Result<(Number<F>, Number<F>), Error> {
+        if n.map_or(false, |n| n > capacity) {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+
+        layouter.assign_region(
+            || "padded fibo",
+            |mut region: Region<'_, F>| {
+                let mut x = (a, b);
+                let mut last = None;
+                for row in 0..=capacity {
+                    let a_cell = region.assign_advice(
+                        || "a",
+                        config.a,
+                        row,
+                        || x.0.ok_or(Error::Synthesis),
+                    )?;
+                    region.assign_advice(|| "b", config.b, row, || x.1.ok_or(Error::Synthesis))?;
+                    let cnt = match row {
+                        0 => region.assign_advice_from_constant(
+                            || "cnt",
+                            config.cnt,
+                            0,
+                            F::zero(),
+                        )?,
+                        _ => {
+                            let value = n.map(|n| F::from(row.min(n) as u64));
+                            region.assign_advice(
+                                || "cnt",
+                                config.cnt,
+                                row,
+                                || value.ok_or(Error::Synthesis),
+                            )?
+                        }
+                    };
+                    last = Some((Number(a_cell), Number(cnt)));
+                    if row == capacity {
+                        break;
+                    }
+
+                    config.s_step.enable(&mut region, row)?;
+                    let enabled = n.map(|n| row < n);
+                    region.assign_advice(
+                        || "e",
+                        config.e,
+                        row,
+                        || {
+                            enabled
+                                .map(|enabled| F::from(enabled as u64))
+                                .ok_or(Error::Synthesis)
+                        },
+                    )?;
+                    if enabled == Some(true) {
+                        x = (x.1, x.0.and_then(|a| x.1.map(|b| a + b)));
+                    }
+                }
+                last.ok_or(Error::Synthesis)
+            },
+        )
+    }
+
+    pub fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        num: Number<F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(num.0.cell(), self.config().instance, row)
+    }
+}
+
+/// Proves that `x_n` of the sequence starting with `a, b` is the public input
+/// in row 0, with `n` the public input in row 1. The keys depend on
+/// `capacity` only, which bounds `n`.
+#[derive(Clone, Debug, Default)]
+pub struct FiboPaddedCircuit<F> {
+    pub a: F,
+    pub b: F,
+    pub n: usize,
+    pub capacity: usize,
+}
+
+impl<F: FieldExt> Circuit<F> for FiboPaddedCircuit<F> {
+    type Config = FiboPaddedConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            capacity: self.capacity,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        FiboPaddedChip::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = FiboPaddedChip::construct(config, ());
+        let (x_n, n) = chip.assign(
+            layouter.namespace(|| "sequence"),
+            Some(self.a),
+            Some(self.b),
+            Some(self.n),
+            self.capacity,
+        )?;
+        chip.expose_public(layouter.namespace(|| "expose x_n"), x_n, 0)?;
+        chip.expose_public(layouter.namespace(|| "expose n"), n, 1)
+    }
+}
@@ -0,0 +1,48 @@
+//! The circuits that have a real-prover flow, shared by the examples and the
+//! `verify` binary.
+//!
+//! A verifying key can only be read back together with the circuit type that
+//! configures its constraint system, so every circuit that may appear in a
+//! proof file is registered here under a stable name.
+
+use std::io;
+
+use halo2_proofs::{
+    pairing::bn256::{Fr, G1Affine},
+    plonk::VerifyingKey,
+    poly::commitment::Params,
+};
+
+pub mod arithmetic;
+pub mod custom_fibo;
+pub mod fibo;
+pub mod fibo_column;
+pub mod fibo_matrix;
+pub mod fibo_padded;
+
+pub const FIBO: &str = "fibo";
+pub const FIBO_PADDED: &str = "fibo_padded";
+pub const CUSTOM_FIBO: &str = "custom_fibo";
+pub const ARITHMETIC: &str = "arithmetic";
+
+/// Reads a verifying key for the circuit registered as `circuit`.
+pub fn read_vk<R: io::Read>(
+    circuit: &str,
+    reader: &mut R,
+    params: &Params<G1Affine>,
+) -> io::Result<VerifyingKey<G1Affine>> {
+    match circuit {
+        FIBO => VerifyingKey::read::<_, fibo::FiboCircuit<Fr>>(reader, params),
+        FIBO_PADDED => {
+            VerifyingKey::read::<_, fibo_padded::FiboPaddedCircuit<Fr>>(reader, params)
+        }
+        CUSTOM_FIBO => {
+            VerifyingKey::read::<_, custom_fibo::CustomFiboCircuit<Fr>>(reader, params)
+        }
+        ARITHMETIC => VerifyingKey::read::<_, arithmetic::MyCircuit<Fr>>(reader, params),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unknown circuit {:?}", circuit),
+        )),
+    }
+}
@@ -0,0 +1,459 @@
+//! A small arithmetic expression language compiled into `FieldChip` circuits.
+//!
+//! Instead of writing an `eval_circuit` method and a new circuit struct for
+//! every formula, an [`ExprCircuit`] takes an expression such as
+//! `(a - b) + (c * d)` together with the values of its variables:
+//!
+//! - private variables are witnessed with `load_private`,
+//! - public variables are copied from the instance column, in the order they
+//!   are declared,
+//! - the result is exposed on the instance column right after the public
+//!   variables.
+//!
+//! Every `+`, `-` and `*` becomes one call on the `FieldInstructions` of a
+//! [`FieldChip`], so the chip computes all intermediate witnesses itself.
+//! Integer literals are constants of the circuit. A literal on either side of
+//! `+` or `*`, or on the right of `-`, goes into the coefficients of the
+//! standard gate through `add_constant`/`mul_constant`; any other literal,
+//! e.g. in `1 - x` or as a whole expression, is loaded with `load_constant`.
+//!
+//! `^` and `&` are XOR and AND of u64 words. They need the chunked lookups of
+//! a [`BitwiseChip`], which [`Expr::synthesize_bitwise`] takes next to the
+//! `FieldChip`; `ExprCircuit` has no table and rejects them. As in Rust, they
+//! bind more loosely than `+` and `-`:
+//!
+//! ```text
+//! expr   := and ("^" and)*
+//! and    := sum ("&" sum)*
+//! sum    := term (("+" | "-") term)*
+//! term   := factor ("*" factor)*
+//! factor := name | integer | "(" expr ")"
+//! ```
+
+use std::{collections::HashMap, fmt};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use crate::gadgets::{
+    add::AddInstructions,
+    bitwise::BitwiseChip,
+    field::{FieldChip, FieldConfig, FieldInstructions},
+    mul::MulInstructions,
+    range,
+    sub::SubInstructions,
+    Number,
+};
+
+/// An arithmetic expression over named variables.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Var(String),
+    Const(u64),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Xor(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+}
+
+/// Returned by [`Expr::parse`] for malformed input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    /// Byte offset of the offending character.
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, ParseError> {
+        Err(ParseError {
+            position: self.position,
+            message: message.into(),
+        })
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.input[self.position..];
+        let trimmed = rest.trim_start();
+        self.position += rest.len() - trimmed.len();
+        trimmed.chars().next()
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.and()?;
+        while let Some('^') = self.peek() {
+            self.position += 1;
+            let rhs = self.and()?;
+            lhs = Expr::Xor(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.sum()?;
+        while let Some('&') = self.peek() {
+            self.position += 1;
+            let rhs = self.sum()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn sum(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.position += 1;
+            let rhs = self.term()?;
+            lhs = match op {
+                '+' => Expr::Add(Box::new(lhs), Box::new(rhs)),
+                _ => Expr::Sub(Box::new(lhs), Box::new(rhs)),
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.factor()?;
+        while let Some('*') = self.peek() {
+            self.position += 1;
+            let rhs = self.factor()?;
+            lhs = Expr::Mul(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn factor(&mut self) -> Result<Expr, ParseError> {
+        match self.peek() {
+            Some('(') => {
+                self.position += 1;
+                let inner = self.expr()?;
+                match self.peek() {
+                    Some(')') => {
+                        self.position += 1;
+                        Ok(inner)
+                    }
+                    _ => self.error("expected ')'"),
+                }
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                let rest = &self.input[self.position..];
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                self.position += len;
+                Ok(Expr::Var(rest[..len].to_string()))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let rest = &self.input[self.position..];
+                let len = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                match rest[..len].parse() {
+                    Ok(value) => {
+                        self.position += len;
+                        Ok(Expr::Const(value))
+                    }
+                    Err(_) => self.error("integer literal too large"),
+                }
+            }
+            Some(c) => self.error(format!("unexpected {:?}", c)),
+            None => self.error("unexpected end of input"),
+        }
+    }
+}
+
+impl Expr {
+    fn as_const(&self) -> Option<u64> {
+        match self {
+            Expr::Const(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Parses an expression such as `(a + b) * c`.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser { input, position: 0 };
+        let expr = parser.expr()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(c) => parser.error(format!("unexpected {:?}", c)),
+        }
+    }
+
+    /// Evaluates the expression natively, returning `None` if a variable has
+    /// no value or an operand of `^` or `&` is not a u64.
+    pub fn eval<F: FieldExt>(&self, values: &HashMap<String, F>) -> Option<F> {
+        let word = |value: F| match range::fits(&value, 64) {
+            true => Some(value.get_lower_128() as u64),
+            false => None,
+        };
+        match self {
+            Expr::Var(name) => values.get(name).copied(),
+            Expr::Const(value) => Some(F::from(*value)),
+            Expr::Add(a, b) => Some(a.eval(values)? + b.eval(values)?),
+            Expr::Sub(a, b) => Some(a.eval(values)? - b.eval(values)?),
+            Expr::Mul(a, b) => Some(a.eval(values)? * b.eval(values)?),
+            Expr::Xor(a, b) => Some(F::from(word(a.eval(values)?)? ^ word(b.eval(values)?)?)),
+            Expr::And(a, b) => Some(F::from(word(a.eval(values)?)? & word(b.eval(values)?)?)),
+        }
+    }
+
+    /// Evaluates the expression on u64s, returning `None` if a variable has no
+    /// value or an operation overflows or underflows.
+    pub fn eval_u64(&self, values: &HashMap<String, u64>) -> Option<u64> {
+        match self {
+            Expr::Var(name) => values.get(name).copied(),
+            Expr::Const(value) => Some(*value),
+            Expr::Add(a, b) => a.eval_u64(values)?.checked_add(b.eval_u64(values)?),
+            Expr::Sub(a, b) => a.eval_u64(values)?.checked_sub(b.eval_u64(values)?),
+            Expr::Mul(a, b) => a.eval_u64(values)?.checked_mul(b.eval_u64(values)?),
+            Expr::Xor(a, b) => Some(a.eval_u64(values)? ^ b.eval_u64(values)?),
+            Expr::And(a, b) => Some(a.eval_u64(values)? & b.eval_u64(values)?),
+        }
+    }
+
+    /// Emits the expression as calls on `field_chip`. `vars` holds the
+    /// already loaded variables. Fails with `Error::Synthesis` on `^` and `&`.
+    pub fn synthesize<F: FieldExt>(
+        &self,
+        field_chip: &FieldChip<F>,
+        layouter: &mut impl Layouter<F>,
+        vars: &HashMap<String, Number<F>>,
+    ) -> Result<Number<F>, Error> {
+        self.emit(field_chip, None, layouter, vars)
+    }
+
+    /// Like [`Expr::synthesize`], with `^` and `&` looked up in the bitwise
+    /// table of `bitwise_chip`, which must hold XOR and AND.
+    pub fn synthesize_bitwise<F: FieldExt>(
+        &self,
+        field_chip: &FieldChip<F>,
+        bitwise_chip: &BitwiseChip<F>,
+        layouter: &mut impl Layouter<F>,
+        vars: &HashMap<String, Number<F>>,
+    ) -> Result<Number<F>, Error> {
+        self.emit(field_chip, Some(bitwise_chip), layouter, vars)
+    }
+
+    fn emit<F: FieldExt>(
+        &self,
+        field_chip: &FieldChip<F>,
+        bitwise_chip: Option<&BitwiseChip<F>>,
+        layouter: &mut impl Layouter<F>,
+        vars: &HashMap<String, Number<F>>,
+    ) -> Result<Number<F>, Error> {
+        match self {
+            Expr::Var(name) => vars.get(name).cloned().ok_or(Error::Synthesis),
+            Expr::Const(value) => {
+                field_chip.load_constant(layouter.namespace(|| "constant"), F::from(*value))
+            }
+            // Addition and multiplication commute, so a constant on either
+            // side goes into the gate's coefficients instead of a cell.
+            Expr::Add(a, b) => match split_const(a, b) {
+                Some((constant, other)) => {
+                    let other = other.emit(field_chip, bitwise_chip, layouter, vars)?;
+                    let constant = F::from(constant);
+                    field_chip.add_constant(layouter.namespace(|| "add constant"), other, constant)
+                }
+                None => {
+                    let a = a.emit(field_chip, bitwise_chip, layouter, vars)?;
+                    let b = b.emit(field_chip, bitwise_chip, layouter, vars)?;
+                    field_chip.add(layouter.namespace(|| "add"), a, b)
+                }
+            },
+            Expr::Sub(a, b) => {
+                let a = a.emit(field_chip, bitwise_chip, layouter, vars)?;
+                match b.as_const() {
+                    Some(constant) => field_chip.add_constant(
+                        layouter.namespace(|| "sub constant"),
+                        a,
+                        -F::from(constant),
+                    ),
+                    None => {
+                        let b = b.emit(field_chip, bitwise_chip, layouter, vars)?;
+                        field_chip.sub(layouter.namespace(|| "sub"), a, b)
+                    }
+                }
+            }
+            Expr::Mul(a, b) => match split_const(a, b) {
+                Some((constant, other)) => {
+                    let other = other.emit(field_chip, bitwise_chip, layouter, vars)?;
+                    let constant = F::from(constant);
+                    field_chip.mul_constant(layouter.namespace(|| "mul constant"), other, constant)
+                }
+                None => {
+                    let a = a.emit(field_chip, bitwise_chip, layouter, vars)?;
+                    let b = b.emit(field_chip, bitwise_chip, layouter, vars)?;
+                    field_chip.mul(layouter.namespace(|| "mul"), a, b)
+                }
+            },
+            Expr::Xor(a, b) | Expr::And(a, b) => {
+                let bitwise_chip = bitwise_chip.ok_or(Error::Synthesis)?;
+                let a = a.emit(field_chip, Some(bitwise_chip), layouter, vars)?;
+                let b = b.emit(field_chip, Some(bitwise_chip), layouter, vars)?;
+                match self {
+                    Expr::Xor(..) => bitwise_chip.xor(layouter.namespace(|| "xor"), &a, &b),
+                    _ => bitwise_chip.and(layouter.namespace(|| "and"), &a, &b),
+                }
+            }
+        }
+    }
+}
+
+/// Returns the constant operand of a commutative operation and the other
+/// operand.
+fn split_const<'a>(a: &'a Expr, b: &'a Expr) -> Option<(u64, &'a Expr)> {
+    match (a.as_const(), b.as_const()) {
+        (Some(constant), _) => Some((constant, b)),
+        (None, Some(constant)) => Some((constant, a)),
+        (None, None) => None,
+    }
+}
+
+/// A circuit proving that `expr` evaluates to the last public input.
+///
+/// The instance column holds the public variables in declaration order,
+/// followed by the result; [`ExprCircuit::public_inputs`] builds it.
+#[derive(Clone, Debug)]
+pub struct ExprCircuit<F: FieldExt> {
+    pub expr: Expr,
+    pub private: Vec<(String, Option<F>)>,
+    pub public: Vec<(String, Option<F>)>,
+}
+
+impl<F: FieldExt> ExprCircuit<F> {
+    /// Returns the instance column: the public variables, then the result.
+    pub fn public_inputs(&self) -> Option<Vec<F>> {
+        let values = self
+            .private
+            .iter()
+            .chain(self.public.iter())
+            .map(|(name, value)| value.map(|value| (name.clone(), value)))
+            .collect::<Option<HashMap<_, _>>>()?;
+
+        let mut public_inputs = self
+            .public
+            .iter()
+            .map(|(_, value)| *value)
+            .collect::<Option<Vec<_>>>()?;
+        public_inputs.push(self.expr.eval(&values)?);
+        Some(public_inputs)
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for ExprCircuit<F> {
+    type Config = FieldConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        let forget = |vars: &[(String, Option<F>)]| {
+            vars.iter().map(|(name, _)| (name.clone(), None)).collect()
+        };
+        Self {
+            expr: self.expr.clone(),
+            private: forget(&self.private),
+            public: forget(&self.public),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+
+        FieldChip::configure(meta, advice, instance)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config, ());
+
+        let mut vars = HashMap::new();
+        for (name, value) in self.private.iter() {
+            let num = field_chip.load_private(layouter.namespace(|| name.as_str()), *value)?;
+            vars.insert(name.clone(), num);
+        }
+        for (row, (name, _)) in self.public.iter().enumerate() {
+            let num = field_chip.load_instance(layouter.namespace(|| name.as_str()), row)?;
+            vars.insert(name.clone(), num);
+        }
+
+        let result = self.expr.synthesize(&field_chip, &mut layouter, &vars)?;
+        field_chip.expose_public(layouter.namespace(|| "expose result"), result, self.public.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::pairing::bn256::Fr as Fp;
+
+    use super::*;
+    use crate::gadgets::test_utils::mock_verifies;
+
+    /// Checks that `expr` verifies with the private variables set to 2, 3, ...
+    /// and the given public ones, and that a wrong result is rejected.
+    fn check(k: u32, expr: &str, private: &[&str], public: &[(&str, u64)]) {
+        let circuit = ExprCircuit {
+            expr: Expr::parse(expr).unwrap(),
+            private: private
+                .iter()
+                .zip(2u64..)
+                .map(|(name, value)| (name.to_string(), Some(Fp::from(value))))
+                .collect(),
+            public: public
+                .iter()
+                .map(|(name, value)| (name.to_string(), Some(Fp::from(*value))))
+                .collect(),
+        };
+
+        let mut public_inputs = circuit.public_inputs().unwrap();
+        assert!(mock_verifies(k, &circuit, vec![public_inputs.clone()]));
+
+        *public_inputs.last_mut().unwrap() += Fp::from(1);
+        assert!(!mock_verifies(k, &circuit, vec![public_inputs]));
+    }
+
+    #[test]
+    fn private_variables() {
+        check(5, "(a + b) + (c + d)", &["a", "b", "c", "d"], &[]);
+        check(5, "(a - b) + (c * d)", &["a", "b", "c", "d"], &[]);
+        check(
+            6,
+            "(a + b + c) + (d + e + f) + (g + h + j)",
+            &["a", "b", "c", "d", "e", "f", "g", "h", "j"],
+            &[],
+        );
+    }
+
+    #[test]
+    fn public_variables() {
+        check(5, "x * x * scale + x", &["x"], &[("scale", 3)]);
+    }
+
+    #[test]
+    fn literals() {
+        check(5, "3 * x * x + 2 * x + 7", &["x"], &[]);
+        check(5, "(x - 1) * (y + 1) * 12", &["x", "y"], &[]);
+        check(5, "1 - x", &["x"], &[]);
+        check(5, "7", &[], &[]);
+    }
+}
@@ -0,0 +1,194 @@
+//! The addition chip: `out = x_0 + ... + x_{N-1}`, with the N inputs in N advice
+//! columns and the output on the next row of the first column.
+//!
+//! `N` trades columns for rows: an 8-input sum takes seven regions with
+//! `AddChip<F, 2>` but a single region with `AddChip<F, 8>`, at the cost of six
+//! more advice columns. `FieldChip` does not use this chip: its additions are
+//! rows of the standard gate, see [`super::standard`].
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+use super::Number;
+
+// ANCHOR: add-instructions
+pub trait AddInstructions<F: FieldExt>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Returns `c = a + b`.
+    fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+}
+
+/// Sums `N` numbers at once.
+pub trait SumInstructions<F: FieldExt, const N: usize>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Returns `x_0 + ... + x_{N-1}`.
+    fn sum(&self, layouter: impl Layouter<F>, inputs: [Self::Num; N]) -> Result<Self::Num, Error>;
+}
+// ANCHOR_END: add-instructions
+
+// ANCHOR: add-config
+#[derive(Clone, Debug)]
+pub struct AddConfig<const N: usize> {
+    pub advice: [Column<Advice>; N],
+    pub s_add: Selector,
+}
+// ANCHOR_END: add-config
+
+// ANCHOR: add-chip
+pub struct AddChip<F: FieldExt, const N: usize> {
+    config: AddConfig<N>,
+    _marker: PhantomData<F>,
+}
+// ANCHOR END: add-chip
+
+// ANCHOR: add-chip-trait-impl
+impl<F: FieldExt, const N: usize> Chip<F> for AddChip<F, N> {
+    type Config = AddConfig<N>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+// ANCHOR END: add-chip-trait-impl
+
+// ANCHOR: add-chip-impl
+impl<F: FieldExt, const N: usize> AddChip<F, N> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; N],
+    ) -> <Self as Chip<F>>::Config {
+        assert!(N >= 2, "an addition needs at least two inputs");
+
+        let s_add = meta.selector();
+        for column in &advice {
+            meta.enable_equality(*column);
+        }
+        // Define our addition gate!
+        meta.create_gate("add", |meta| {
+            let sum = advice
+                .iter()
+                .map(|column| meta.query_advice(*column, Rotation::cur()))
+                .reduce(|acc, input| acc + input)
+                .unwrap();
+            let out = meta.query_advice(advice[0], Rotation::next());
+            let s_add = meta.query_selector(s_add);
+
+            vec![s_add * (sum - out)]
+        });
+
+        AddConfig { advice, s_add }
+    }
+
+    /// Sums any number of inputs by chaining `N`-input additions: the first
+    /// region adds `N` inputs, and every further region adds the running sum
+    /// and `N - 1` new inputs.
+    ///
+    /// The chip has no way to pad a region, so `inputs.len() - 1` must be a
+    /// multiple of `N - 1`.
+    pub fn sum_all(
+        &self,
+        mut layouter: impl Layouter<F>,
+        inputs: Vec<Number<F>>,
+    ) -> Result<Number<F>, Error> {
+        if inputs.is_empty() || (inputs.len() - 1) % (N - 1) != 0 {
+            return Err(Error::Synthesis);
+        }
+
+        let mut inputs = inputs.into_iter();
+        let mut acc = inputs.next().unwrap();
+        let rest: Vec<_> = inputs.collect();
+        for chunk in rest.chunks(N - 1) {
+            let mut row = vec![acc];
+            row.extend_from_slice(chunk);
+            let row: [Number<F>; N] = row.try_into().map_err(|_| Error::Synthesis)?;
+            acc = self.sum(layouter.namespace(|| "sum"), row)?;
+        }
+        Ok(acc)
+    }
+}
+// ANCHOR END: add-chip-impl
+
+// ANCHOR: add-instructions-impl
+impl<F: FieldExt, const N: usize> SumInstructions<F, N> for AddChip<F, N> {
+    type Num = Number<F>;
+
+    fn sum(&self, mut layouter: impl Layouter<F>, inputs: [Self::Num; N]) -> Result<Self::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "add",
+            |mut region: Region<'_, F>| {
+                // We only want to use a single addition gate in this region,
+                // so we enable it at region offset 0; this means it will constrain
+                // cells at offsets 0 and 1.
+                config.s_add.enable(&mut region, 0)?;
+
+                // The inputs we've been given could be located anywhere in the circuit,
+                // but we can only rely on relative offsets inside this region. So we
+                // assign new cells inside the region and constrain them to have the
+                // same values as the inputs.
+                for (input, column) in inputs.iter().zip(config.advice.iter()) {
+                    input.0.copy_advice(|| "input", &mut region, *column, 0)?;
+                }
+
+                // Now we can compute the addition result, which is to be assigned
+                // into the output position.
+                let value = inputs
+                    .iter()
+                    .try_fold(F::zero(), |acc, input| input.0.value().map(|v| acc + *v));
+
+                // Finally, we do the assignment to the output, returning a
+                // variable to be used in another part of the circuit.
+                region
+                    .assign_advice(
+                        || "sum",
+                        config.advice[0],
+                        1,
+                        || value.ok_or(Error::Synthesis),
+                    )
+                    .map(Number)
+            },
+        )
+    }
+}
+
+impl<F: FieldExt> AddInstructions<F> for AddChip<F, 2> {
+    type Num = Number<F>;
+
+    fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        self.sum(layouter, [a, b])
+    }
+}
+// ANCHOR END: add-instructions-impl
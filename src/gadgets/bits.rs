@@ -0,0 +1,328 @@
+//! The bit decomposition chip: splits a number into boolean cells and proves
+//! that they recompose to it.
+//!
+//! The bits are laid out most significant first next to a running sum that
+//! doubles on every row, so each step only looks one row back:
+//!
+//! | bit     | acc                         | q_bool | q_first | q_step |
+//! |---------|-----------------------------|--------|---------|--------|
+//! | b_{n-1} | b_{n-1}                     | 1      | 1       |        |
+//! | b_{n-2} | 2 * b_{n-1} + b_{n-2}       | 1      |         | 1      |
+//! | ...     | ...                         | 1      |         | 1      |
+//! | b_0     | x                           | 1      |         | 1      |
+//!
+//! `q_step` constrains `acc = 2 * acc_prev + bit` with `Rotation::prev`, and
+//! the last `acc` is a copy of `x`, which is the recomposition check. A value
+//! that does not fit in `n` bits leaves the last step unsatisfied.
+//!
+//! [`BitsInstructions::to_bits`] returns the bits little-endian, i.e. in the
+//! opposite order of the rows.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::{range, Number};
+
+pub trait BitsInstructions<F: FieldExt>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Decomposes `a` into `n` little-endian bits, each constrained to be
+    /// boolean. Fails with `Error::Synthesis` if `n` exceeds the capacity of
+    /// the field, where the decomposition would not be unique.
+    fn to_bits(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        n: usize,
+    ) -> Result<Vec<Self::Num>, Error>;
+
+    /// Returns the number whose little-endian bits are `bits`, constraining
+    /// each of them to be boolean.
+    fn from_bits(&self, layouter: impl Layouter<F>, bits: &[Self::Num])
+        -> Result<Self::Num, Error>;
+}
+
+#[derive(Clone, Debug)]
+pub struct BitsConfig {
+    /// The bits and the running sum.
+    pub advice: [Column<Advice>; 2],
+    pub q_bool: Selector,
+    pub q_first: Selector,
+    pub q_step: Selector,
+}
+
+pub struct BitsChip<F: FieldExt> {
+    config: BitsConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for BitsChip<F> {
+    type Config = BitsConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> BitsChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+    ) -> <Self as Chip<F>>::Config {
+        for column in &advice {
+            meta.enable_equality(*column);
+        }
+        let q_bool = meta.selector();
+        let q_first = meta.selector();
+        let q_step = meta.selector();
+
+        meta.create_gate("bits", |meta| {
+            let bit = meta.query_advice(advice[0], Rotation::cur());
+            let acc = meta.query_advice(advice[1], Rotation::cur());
+            let acc_prev = meta.query_advice(advice[1], Rotation::prev());
+            let q_bool = meta.query_selector(q_bool);
+            let q_first = meta.query_selector(q_first);
+            let q_step = meta.query_selector(q_step);
+
+            let one = Expression::Constant(F::one());
+            let two = Expression::Constant(F::from(2));
+            vec![
+                q_bool * bit.clone() * (one - bit.clone()),
+                q_first * (acc.clone() - bit.clone()),
+                q_step * (acc - two * acc_prev - bit),
+            ]
+        });
+
+        BitsConfig {
+            advice,
+            q_bool,
+            q_first,
+            q_step,
+        }
+    }
+
+    /// Lays out the bits most significant first with the running sum next to
+    /// them. `assign_bit` places bit `i` (little-endian) on a row, and the last
+    /// sum is copied from `a` if given. Returns the bits, little-endian, and
+    /// the final sum.
+    fn assign_bits(
+        &self,
+        region: &mut Region<'_, F>,
+        n: usize,
+        a: Option<&Number<F>>,
+        mut assign_bit: impl FnMut(&mut Region<'_, F>, usize, usize) -> Result<Number<F>, Error>,
+    ) -> Result<(Vec<Number<F>>, Number<F>), Error> {
+        let config = self.config();
+
+        let mut bits = vec![];
+        let mut acc: Option<Number<F>> = None;
+        for row in 0..n {
+            let i = n - 1 - row;
+            config.q_bool.enable(region, row)?;
+            if row == 0 {
+                config.q_first.enable(region, row)?;
+            } else {
+                config.q_step.enable(region, row)?;
+            }
+
+            let bit = assign_bit(region, row, i)?;
+            let value = bit.0.value().and_then(|bit| match &acc {
+                Some(acc) => acc.0.value().map(|acc| acc.double() + *bit),
+                None => Some(*bit),
+            });
+            acc = Some(match a {
+                // The recomposition check: the sum of all bits is `a`.
+                Some(a) if row == n - 1 => {
+                    Number(a.0.copy_advice(|| "x", region, config.advice[1], row)?)
+                }
+                _ => region
+                    .assign_advice(
+                        || format!("acc_{}", i),
+                        config.advice[1],
+                        row,
+                        || value.ok_or(Error::Synthesis),
+                    )
+                    .map(Number)?,
+            });
+            bits.push(bit);
+        }
+
+        bits.reverse();
+        Ok((bits, acc.ok_or(Error::Synthesis)?))
+    }
+}
+
+impl<F: FieldExt> BitsInstructions<F> for BitsChip<F> {
+    type Num = Number<F>;
+
+    fn to_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Self::Num,
+        n: usize,
+    ) -> Result<Vec<Self::Num>, Error> {
+        if n == 0 || n > F::CAPACITY as usize {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+        let values = a.0.value().map(|a| range::decompose(a, 1, n));
+
+        layouter.assign_region(
+            || "to bits",
+            |mut region: Region<'_, F>| {
+                let (bits, _) = self.assign_bits(&mut region, n, Some(a), |region, row, i| {
+                    region
+                        .assign_advice(
+                            || format!("bit_{}", i),
+                            config.advice[0],
+                            row,
+                            || {
+                                values
+                                    .as_ref()
+                                    .map(|values| F::from(values[i]))
+                                    .ok_or(Error::Synthesis)
+                            },
+                        )
+                        .map(Number)
+                })?;
+                Ok(bits)
+            },
+        )
+    }
+
+    fn from_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bits: &[Self::Num],
+    ) -> Result<Self::Num, Error> {
+        if bits.is_empty() {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+
+        layouter.assign_region(
+            || "from bits",
+            |mut region: Region<'_, F>| {
+                let (_, acc) =
+                    self.assign_bits(&mut region, bits.len(), None, |region, row, i| {
+                        bits[i]
+                            .0
+                            .copy_advice(|| format!("bit_{}", i), region, config.advice[0], row)
+                            .map(Number)
+                    })?;
+                Ok(acc)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        pairing::bn256::Fr as Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    use super::*;
+    use crate::gadgets::{
+        field::{FieldChip, FieldConfig, FieldInstructions},
+        test_utils::mock_verifies,
+    };
+
+    /// Splits a private `a` into `n` bits, exposing the recomposed number in
+    /// row 0 and the least significant bit in row 1.
+    #[derive(Default)]
+    struct BitsCircuit {
+        a: Option<Fp>,
+        n: usize,
+    }
+
+    impl Circuit<Fp> for BitsCircuit {
+        type Config = (FieldConfig, BitsConfig);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                n: self.n,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [meta.advice_column(), meta.advice_column()];
+            let instance = meta.instance_column();
+            (
+                FieldChip::configure(meta, advice, instance),
+                BitsChip::configure(meta, advice),
+            )
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let field_chip = FieldChip::construct(config.0, ());
+            let bits_chip = BitsChip::construct(config.1, ());
+
+            let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+            let bits = bits_chip.to_bits(layouter.namespace(|| "to bits"), &a, self.n)?;
+            let a = bits_chip.from_bits(layouter.namespace(|| "from bits"), &bits)?;
+            field_chip.expose_public(layouter.namespace(|| "expose a"), a, 0)?;
+            field_chip.expose_public(layouter.namespace(|| "expose bit 0"), bits[0].clone(), 1)
+        }
+    }
+
+    fn verifies(a: u64, n: usize) -> bool {
+        let circuit = BitsCircuit {
+            a: Some(Fp::from(a)),
+            n,
+        };
+        mock_verifies(8, &circuit, vec![vec![Fp::from(a), Fp::from(a & 1)]])
+    }
+
+    #[test]
+    fn round_trip() {
+        assert!(verifies(0xbeef, 16));
+        assert!(verifies(0xffff, 16));
+        assert!(verifies(0, 16));
+    }
+
+    #[test]
+    fn too_wide() {
+        // 2^16 does not recompose from its low 16 bits.
+        assert!(!verifies(0x1_0000, 16));
+        assert!(!verifies(2, 1));
+        assert!(verifies(1, 1));
+    }
+
+    #[test]
+    fn no_bits() {
+        let circuit = BitsCircuit {
+            a: Some(Fp::from(0)),
+            n: 0,
+        };
+        assert!(MockProver::run(8, &circuit, vec![vec![Fp::from(0), Fp::from(0)]]).is_err());
+    }
+}
@@ -0,0 +1,400 @@
+//! A lookup table for bitwise operations on small operands.
+//!
+//! One table holds any set of operations at one operand width. Each row is
+//! tagged with the opcode of its operation:
+//!
+//! | opcode | lhs | rhs | out           |
+//! |--------|-----|-----|---------------|
+//! | 0      | 0   | 0   | 0             |
+//! | 1      | x   | y   | x ^ y         |
+//! | 2      | x   | y   | x & y         |
+//! | 3      | x   | y   | x \| y        |
+//! | 4      | x   | 0   | !x            |
+//!
+//! so a chip needs a single lookup argument for all of them: it looks up
+//! `(opcode, lhs, rhs, out)` with the opcode taken from a fixed column. The
+//! all-zero row is what a disabled lookup row evaluates to.
+//!
+//! [`BitwiseTableChip::load`] fills the table and returns a [`BitwiseTable`]
+//! describing what it holds. Chips that look up into it take that value as
+//! their `Chip::Loaded`, so they are constructed only after the table has been
+//! loaded, and they know its width and operations.
+//!
+//! [`BitwiseChip`] is such a chip for u64 words. A lookup table over whole
+//! words is out of the question, so [`BitwiseChip::bitwise`] splits both
+//! operands and the result into chunks of the table's width and looks up one
+//! chunk triple per row. Each column holds a running sum, `z_0` being the
+//! operand itself and `z_{i+1} = (z_i - chunk_i) / 2^chunk_bits`, so a chunk
+//! is `z_i - 2^chunk_bits * z_{i+1}`:
+//!
+//! | a         | b         | out         | s_bitwise | opcode | chunk_shift    |
+//! |-----------|-----------|-------------|-----------|--------|----------------|
+//! | a         | b         | a ^ b       | 1         | xor    | 2^chunk_bits   |
+//! | z_a,1     | z_b,1     | z_out,1     | 1         | xor    | 2^chunk_bits   |
+//! | ...       | ...       | ...         | ...       | ...    | ...            |
+//! | z_a,m-1   | z_b,m-1   | z_out,m-1   | 1         | xor    | 0              |
+//!
+//! `chunk_shift` is zero on the last row, where the chunk is all that is left
+//! of the running sum. As every chunk is in the table, the recomposed operands
+//! are below `2^64`; a value that is not fails the lookup instead of producing
+//! a witness that only the native `^` agrees with. AND, OR and NOT use the
+//! same rows with their own opcode; NOT leaves `b` at zero.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector, TableColumn},
+    poly::Rotation,
+};
+
+use super::Number;
+
+/// The width of the words [`BitwiseChip`] works on.
+pub const WORD_BITS: usize = 64;
+
+/// The default chunk width of a [`BitwiseChip`], i.e. tables of 256 rows per
+/// binary operation.
+pub const CHUNK_BITS: usize = 4;
+
+/// A bitwise operation on words, looked up chunk by chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitwiseOp {
+    Xor,
+    And,
+    Or,
+    /// Unary; the right operand is zero.
+    Not,
+}
+
+impl BitwiseOp {
+    /// The tag of this operation in the table. Zero is reserved for the
+    /// all-zero row.
+    pub fn opcode(self) -> u64 {
+        match self {
+            BitwiseOp::Xor => 1,
+            BitwiseOp::And => 2,
+            BitwiseOp::Or => 3,
+            BitwiseOp::Not => 4,
+        }
+    }
+
+    pub fn apply(self, lhs: u64, rhs: u64) -> u64 {
+        match self {
+            BitwiseOp::Xor => lhs ^ rhs,
+            BitwiseOp::And => lhs & rhs,
+            BitwiseOp::Or => lhs | rhs,
+            BitwiseOp::Not => !lhs,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BitwiseOp::Xor => "xor",
+            BitwiseOp::And => "and",
+            BitwiseOp::Or => "or",
+            BitwiseOp::Not => "not",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitwiseTableConfig {
+    pub opcode: TableColumn,
+    pub lhs: TableColumn,
+    pub rhs: TableColumn,
+    pub out: TableColumn,
+}
+
+/// The widest operand [`BitwiseTableChip::load`] accepts. A binary operation
+/// takes `2^(2 * bits)` rows, and bn256 has no domain beyond `2^28` rows.
+pub const MAX_BITS: usize = 13;
+
+/// What a loaded bitwise table holds.
+#[derive(Clone, Debug)]
+pub struct BitwiseTable {
+    pub ops: Vec<BitwiseOp>,
+    /// Operand width.
+    pub bits: usize,
+}
+
+impl BitwiseTable {
+    pub fn supports(&self, op: BitwiseOp) -> bool {
+        self.ops.contains(&op)
+    }
+}
+
+pub struct BitwiseTableChip<F: FieldExt> {
+    config: BitwiseTableConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for BitwiseTableChip<F> {
+    type Config = BitwiseTableConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> BitwiseTableChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> <Self as Chip<F>>::Config {
+        BitwiseTableConfig {
+            opcode: meta.lookup_table_column(),
+            lhs: meta.lookup_table_column(),
+            rhs: meta.lookup_table_column(),
+            out: meta.lookup_table_column(),
+        }
+    }
+
+    /// Fills the table with `ops` on every `bits`-bit operand, which takes
+    /// `2^(2 * bits)` rows per binary operation and `2^bits` rows for `Not`.
+    ///
+    /// Fails with `Error::Synthesis` unless `0 < bits <= MAX_BITS`.
+    pub fn load(
+        &self,
+        mut layouter: impl Layouter<F>,
+        ops: &[BitwiseOp],
+        bits: usize,
+    ) -> Result<BitwiseTable, Error> {
+        if bits == 0 || bits > MAX_BITS {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+        let mask = (1u64 << bits) - 1;
+
+        layouter.assign_table(
+            || "bitwise",
+            |mut table| {
+                let mut row = 0;
+                let mut assign = |opcode: u64, lhs: u64, rhs: u64, out: u64| -> Result<(), Error> {
+                    for (name, column, value) in [
+                        ("opcode", config.opcode, opcode),
+                        ("lhs", config.lhs, lhs),
+                        ("rhs", config.rhs, rhs),
+                        ("out", config.out, out),
+                    ] {
+                        table.assign_cell(|| name, column, row, || Ok(F::from(value)))?;
+                    }
+                    row += 1;
+                    Ok(())
+                };
+
+                assign(0, 0, 0, 0)?;
+                for op in ops {
+                    let rhs_values = match op {
+                        BitwiseOp::Not => 0..1,
+                        _ => 0..1 << bits,
+                    };
+                    for lhs in 0..1 << bits {
+                        for rhs in rhs_values.clone() {
+                            assign(op.opcode(), lhs, rhs, op.apply(lhs, rhs) & mask)?;
+                        }
+                    }
+                }
+                Ok(())
+            },
+        )?;
+
+        Ok(BitwiseTable {
+            ops: ops.to_vec(),
+            bits,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitwiseConfig {
+    /// The running sums of `a`, `b` and `out`.
+    pub advice: [Column<Advice>; 3],
+    pub s_bitwise: Selector,
+    /// The opcode of the operation looked up on a row.
+    pub opcode: Column<Fixed>,
+    /// `2^chunk_bits` on every row of a bitwise region but the last.
+    pub chunk_shift: Column<Fixed>,
+    pub table: BitwiseTableConfig,
+}
+
+pub struct BitwiseChip<F: FieldExt> {
+    config: BitwiseConfig,
+    table: BitwiseTable,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for BitwiseChip<F> {
+    type Config = BitwiseConfig;
+    type Loaded = BitwiseTable;
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &self.table
+    }
+}
+
+impl<F: FieldExt> BitwiseChip<F> {
+    /// `table` is what [`BitwiseTableChip::load`] filled `config.table` with;
+    /// its width is the chunk width and must divide 64.
+    pub fn construct(config: <Self as Chip<F>>::Config, table: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            table,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the chunk lookup on `advice` into `table`. `s_bitwise` must
+    /// be a complex selector, since it is used in a lookup.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 3],
+        s_bitwise: Selector,
+        table: BitwiseTableConfig,
+    ) -> <Self as Chip<F>>::Config {
+        for column in &advice {
+            meta.enable_equality(*column);
+        }
+        let opcode = meta.fixed_column();
+        let chunk_shift = meta.fixed_column();
+
+        meta.lookup("bitwise", |meta| {
+            let s_bitwise = meta.query_selector(s_bitwise);
+            let opcode = meta.query_fixed(opcode, Rotation::cur());
+            let shift = meta.query_fixed(chunk_shift, Rotation::cur());
+            let mut chunk = |column: Column<Advice>| {
+                let cur = meta.query_advice(column, Rotation::cur());
+                let next = meta.query_advice(column, Rotation::next());
+                s_bitwise.clone() * (cur - shift.clone() * next)
+            };
+            vec![
+                (chunk(advice[0]), table.lhs),
+                (chunk(advice[1]), table.rhs),
+                (chunk(advice[2]), table.out),
+                (s_bitwise.clone() * opcode, table.opcode),
+            ]
+        });
+
+        BitwiseConfig {
+            advice,
+            s_bitwise,
+            opcode,
+            chunk_shift,
+            table,
+        }
+    }
+
+    /// Returns `op(a, b)` for `a, b < 2^64`, looking up one chunk of each
+    /// operand per row. `b` is ignored for `Not`.
+    pub fn bitwise(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: BitwiseOp,
+        a: &Number<F>,
+        b: &Number<F>,
+    ) -> Result<Number<F>, Error> {
+        let chunk_bits = self.table.bits;
+        if !self.table.supports(op) || chunk_bits == 0 || WORD_BITS % chunk_bits != 0 {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+        let num_chunks = WORD_BITS / chunk_bits;
+        let unary = op == BitwiseOp::Not;
+
+        // Operands wider than 64 bits are truncated here; the lookup on the
+        // first row then rejects them.
+        let words = a.0.value().and_then(|a| {
+            b.0.value().map(|b| {
+                let a_val = a.get_lower_128() as u64;
+                let b_val = if unary { 0 } else { b.get_lower_128() as u64 };
+                [a_val, b_val, op.apply(a_val, b_val)]
+            })
+        });
+
+        layouter.assign_region(
+            || op.name(),
+            |mut region| {
+                let mut out = None;
+                for row in 0..num_chunks {
+                    config.s_bitwise.enable(&mut region, row)?;
+                    let shift = if row + 1 < num_chunks {
+                        F::from(1 << chunk_bits)
+                    } else {
+                        F::zero()
+                    };
+                    let opcode = F::from(op.opcode());
+                    region.assign_fixed(|| "opcode", config.opcode, row, || Ok(opcode))?;
+                    region.assign_fixed(|| "chunk shift", config.chunk_shift, row, || Ok(shift))?;
+
+                    if row == 0 {
+                        a.0.copy_advice(|| "lhs", &mut region, config.advice[0], 0)?;
+                        if !unary {
+                            b.0.copy_advice(|| "rhs", &mut region, config.advice[1], 0)?;
+                        }
+                    }
+                    for (column, name) in [(0, "z_lhs"), (1, "z_rhs"), (2, "z_out")] {
+                        if row == 0 && (column == 0 || (column == 1 && !unary)) {
+                            continue;
+                        }
+                        let value = words.map(|words| F::from(words[column] >> (row * chunk_bits)));
+                        let cell = region.assign_advice(
+                            || name,
+                            config.advice[column],
+                            row,
+                            || value.ok_or(Error::Synthesis),
+                        )?;
+                        if row == 0 && column == 2 {
+                            out = Some(Number(cell));
+                        }
+                    }
+                }
+                out.ok_or(Error::Synthesis)
+            },
+        )
+    }
+
+    pub fn xor(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Number<F>,
+        b: &Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.bitwise(layouter, BitwiseOp::Xor, a, b)
+    }
+
+    pub fn and(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Number<F>,
+        b: &Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.bitwise(layouter, BitwiseOp::And, a, b)
+    }
+
+    pub fn or(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Number<F>,
+        b: &Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.bitwise(layouter, BitwiseOp::Or, a, b)
+    }
+
+    pub fn not(&self, layouter: impl Layouter<F>, a: &Number<F>) -> Result<Number<F>, Error> {
+        self.bitwise(layouter, BitwiseOp::Not, a, a)
+    }
+}
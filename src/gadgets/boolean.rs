@@ -0,0 +1,121 @@
+//! Booleans and branching on top of the standard gate.
+//!
+//! A boolean is a [`Number`] that is zero or one. Every operation here is one
+//! or two standard-gate rows:
+//!
+//! | operation          | rows                                       |
+//! |--------------------|--------------------------------------------|
+//! | `assert_bool(a)`   | `a * a - a = 0`                            |
+//! | `not(a)`           | `c = 1 - a`                                |
+//! | `and(a, b)`        | `c = a * b`                                |
+//! | `or(a, b)`         | `c = a + b - a * b`                        |
+//! | `select(s, a, b)`  | `t = a - b`, `u = s * t`, `c = u + b`      |
+//!
+//! `not`, `and`, `or` and `select` do not check that their flags are
+//! booleans. Flags coming from [`super::is_zero`] or [`super::compare`] are
+//! constrained already; a flag witnessed by the prover must go through
+//! `assert_bool` first, otherwise `select` with `s = 2` returns `2a - b`.
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter},
+    plonk::Error,
+};
+
+use super::{
+    add::AddInstructions,
+    field::FieldChip,
+    mul::MulInstructions,
+    standard::{Coefficients, StandardChip, StandardInstructions},
+    sub::SubInstructions,
+    Number,
+};
+
+pub trait BoolInstructions<F: FieldExt>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Constrains `a` to be zero or one.
+    fn assert_bool(&self, layouter: impl Layouter<F>, a: &Self::Num) -> Result<(), Error>;
+
+    /// Returns `1 - a`.
+    fn not(&self, layouter: impl Layouter<F>, a: Self::Num) -> Result<Self::Num, Error>;
+
+    /// Returns `a AND b`.
+    fn and(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns `a OR b`.
+    fn or(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns `a` if `cond` is one and `b` if it is zero.
+    fn select(
+        &self,
+        layouter: impl Layouter<F>,
+        cond: Self::Num,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+}
+
+impl<F: FieldExt> BoolInstructions<F> for FieldChip<F> {
+    type Num = Number<F>;
+
+    fn assert_bool(&self, layouter: impl Layouter<F>, a: &Self::Num) -> Result<(), Error> {
+        let config = self.config().standard_config.clone();
+        let standard_chip = StandardChip::<F>::construct(config, ());
+        let coeffs = Coefficients {
+            q_l: -F::one(),
+            q_r: F::zero(),
+            q_m: F::one(),
+            q_o: F::zero(),
+            q_c: F::zero(),
+        };
+        standard_chip.constrain(layouter, a, a, coeffs)
+    }
+
+    fn not(&self, layouter: impl Layouter<F>, a: Self::Num) -> Result<Self::Num, Error> {
+        let coeffs = Coefficients::output(-F::one(), F::zero(), F::zero(), F::one());
+        self.combine(layouter, a, None, coeffs)
+    }
+
+    fn and(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        self.mul(layouter, a, b)
+    }
+
+    fn or(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let coeffs = Coefficients::output(F::one(), F::one(), -F::one(), F::zero());
+        self.combine(layouter, a, Some(b), coeffs)
+    }
+
+    fn select(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cond: Self::Num,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let diff = self.sub(layouter.namespace(|| "a - b"), a, b.clone())?;
+        let scaled = self.mul(layouter.namespace(|| "cond * (a - b)"), cond, diff)?;
+        self.add(layouter.namespace(|| "cond * (a - b) + b"), scaled, b)
+    }
+}
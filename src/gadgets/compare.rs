@@ -0,0 +1,362 @@
+//! Comparisons of numbers known to fit in `n` bits.
+//!
+//! For `a, b < 2^n`, `a - b` lies in `(-2^n, 2^n)`, so `a < b` exactly when
+//! adding `2^n` is needed to bring it into `[0, 2^n)`. The chip witnesses that
+//! bit as `out` and the remainder `r`, and constrains
+//!
+//! ```text
+//! a - b - bias + 2^n * out = r,    out * (1 - out) = 0,    r < 2^n
+//! ```
+//!
+//! with `bias = 0` for `a < b` and `bias = 1` for `a <= b`, i.e. `a < b + 1`.
+//! Only one value of `out` leaves `r` in range, so `out` is the result.
+//!
+//! | a0  | a1 | s_cmp | two_pow_bits | bias |
+//! |-----|----|-------|--------------|------|
+//! | a   | b  | 1     | 2^n          | bias |
+//! | out | r  |       |              |      |
+//!
+//! `r`, `a` and `b` are all range checked with a [`RangeCheckChip`], so the
+//! inputs do not need to be checked beforehand.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::{
+    range::{self, RangeCheckChip, RangeCheckConfig, RangeCheckInstructions},
+    Number,
+};
+
+pub trait CompareInstructions<F: FieldExt>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Returns 1 if `a < b` and 0 otherwise, for `a, b < 2^bits`.
+    fn lt(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns 1 if `a <= b` and 0 otherwise, for `a, b < 2^bits`.
+    fn le(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns 1 if `a > b` and 0 otherwise, for `a, b < 2^bits`.
+    fn gt(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<Self::Num, Error>;
+}
+
+#[derive(Clone, Debug)]
+pub struct CompareConfig {
+    pub advice: [Column<Advice>; 2],
+    pub s_cmp: Selector,
+    pub two_pow_bits: Column<Fixed>,
+    pub bias: Column<Fixed>,
+    pub range_config: RangeCheckConfig,
+}
+
+pub struct CompareChip<F: FieldExt> {
+    config: CompareConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for CompareChip<F> {
+    type Config = CompareConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> CompareChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the comparison gate on `advice`. The range checks go
+    /// through `range_config`, whose table the circuit has to load.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        range_config: RangeCheckConfig,
+    ) -> <Self as Chip<F>>::Config {
+        for column in &advice {
+            meta.enable_equality(*column);
+        }
+        let s_cmp = meta.selector();
+        let two_pow_bits = meta.fixed_column();
+        let bias = meta.fixed_column();
+
+        meta.create_gate("compare", |meta| {
+            let s_cmp = meta.query_selector(s_cmp);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let out = meta.query_advice(advice[0], Rotation::next());
+            let r = meta.query_advice(advice[1], Rotation::next());
+            let two_pow_bits = meta.query_fixed(two_pow_bits, Rotation::cur());
+            let bias = meta.query_fixed(bias, Rotation::cur());
+
+            let one = Expression::Constant(F::one());
+            vec![
+                s_cmp.clone() * (a - b - bias + two_pow_bits * out.clone() - r),
+                s_cmp * out.clone() * (one - out),
+            ]
+        });
+
+        CompareConfig {
+            advice,
+            s_cmp,
+            two_pow_bits,
+            bias,
+            range_config,
+        }
+    }
+
+    /// Returns whether `a < b + bias`, see the module documentation.
+    fn compare(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Number<F>,
+        b: &Number<F>,
+        bits: usize,
+        bias: F,
+    ) -> Result<Number<F>, Error> {
+        // `r` takes values up to 2^(bits + 1), which must not wrap around.
+        if bits >= F::CAPACITY as usize {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+        let two_pow_bits = F::from(2).pow(&[bits as u64, 0, 0, 0]);
+
+        // With `out = 0` the remainder is `a - b - bias`; if that is not in
+        // range, the answer is `out = 1`.
+        let values = a.0.value().zip(b.0.value()).map(|(a, b)| {
+            let r = *a - *b - bias;
+            if range::fits(&r, bits) {
+                (F::zero(), r)
+            } else {
+                (F::one(), r + two_pow_bits)
+            }
+        });
+
+        let (out, r) = layouter.assign_region(
+            || "compare",
+            |mut region: Region<'_, F>| {
+                config.s_cmp.enable(&mut region, 0)?;
+                region.assign_fixed(|| "2^bits", config.two_pow_bits, 0, || Ok(two_pow_bits))?;
+                region.assign_fixed(|| "bias", config.bias, 0, || Ok(bias))?;
+
+                a.0.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.0.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+
+                let out = region.assign_advice(
+                    || "out",
+                    config.advice[0],
+                    1,
+                    || values.map(|(out, _)| out).ok_or(Error::Synthesis),
+                )?;
+                let r = region.assign_advice(
+                    || "r",
+                    config.advice[1],
+                    1,
+                    || values.map(|(_, r)| r).ok_or(Error::Synthesis),
+                )?;
+                Ok((Number(out), Number(r)))
+            },
+        )?;
+
+        let range_chip = RangeCheckChip::<F>::construct(config.range_config.clone(), ());
+        range_chip.range_check(layouter.namespace(|| "a < 2^bits"), a, bits)?;
+        range_chip.range_check(layouter.namespace(|| "b < 2^bits"), b, bits)?;
+        range_chip.range_check(layouter.namespace(|| "r < 2^bits"), &r, bits)?;
+
+        Ok(out)
+    }
+}
+
+impl<F: FieldExt> CompareInstructions<F> for CompareChip<F> {
+    type Num = Number<F>;
+
+    fn lt(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<Self::Num, Error> {
+        self.compare(layouter, a, b, bits, F::zero())
+    }
+
+    fn le(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<Self::Num, Error> {
+        self.compare(layouter, a, b, bits, F::one())
+    }
+
+    fn gt(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<Self::Num, Error> {
+        self.compare(layouter, b, a, bits, F::zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        pairing::bn256::Fr as Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    use super::*;
+    use crate::gadgets::{
+        field::{FieldChip, FieldConfig, FieldInstructions},
+        test_utils::mock_verifies,
+    };
+
+    #[derive(Clone, Copy, Debug)]
+    enum Op {
+        Lt,
+        Le,
+        Gt,
+    }
+
+    /// Compares the private `a` and `b` as `bits`-bit numbers and exposes the
+    /// result in row 0.
+    struct CompareCircuit {
+        a: Option<Fp>,
+        b: Option<Fp>,
+        op: Op,
+        bits: usize,
+    }
+
+    impl Circuit<Fp> for CompareCircuit {
+        type Config = (FieldConfig, RangeCheckConfig, CompareConfig);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                a: None,
+                b: None,
+                op: self.op,
+                bits: self.bits,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [meta.advice_column(), meta.advice_column()];
+            let instance = meta.instance_column();
+            let table = meta.lookup_table_column();
+
+            let field_config = FieldChip::configure(meta, advice, instance);
+            let range_config = RangeCheckChip::configure(meta, advice[0], table, 8);
+            let compare_config = CompareChip::configure(meta, advice, range_config.clone());
+            (field_config, range_config, compare_config)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let field_chip = FieldChip::construct(config.0, ());
+            let range_chip = RangeCheckChip::construct(config.1, ());
+            let compare_chip = CompareChip::construct(config.2, ());
+            range_chip.load_table(layouter.namespace(|| "range table"))?;
+
+            let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+            let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+            let layouter_cmp = layouter.namespace(|| "compare");
+            let out = match self.op {
+                Op::Lt => compare_chip.lt(layouter_cmp, &a, &b, self.bits)?,
+                Op::Le => compare_chip.le(layouter_cmp, &a, &b, self.bits)?,
+                Op::Gt => compare_chip.gt(layouter_cmp, &a, &b, self.bits)?,
+            };
+            field_chip.expose_public(layouter.namespace(|| "expose out"), out, 0)
+        }
+    }
+
+    /// Whether `op(a, b) == expected` verifies for `bits`-bit operands.
+    fn verifies(op: Op, a: u64, b: u64, bits: usize, expected: bool) -> bool {
+        let circuit = CompareCircuit {
+            a: Some(Fp::from(a)),
+            b: Some(Fp::from(b)),
+            op,
+            bits,
+        };
+        mock_verifies(9, &circuit, vec![vec![Fp::from(expected as u64)]])
+    }
+
+    #[test]
+    fn equal_inputs() {
+        for bits in [8, 10] {
+            for a in [0, 5, (1 << bits) - 1] {
+                assert!(verifies(Op::Lt, a, a, bits, false));
+                assert!(verifies(Op::Le, a, a, bits, true));
+                assert!(verifies(Op::Gt, a, a, bits, false));
+                assert!(!verifies(Op::Lt, a, a, bits, true));
+                assert!(!verifies(Op::Le, a, a, bits, false));
+            }
+        }
+    }
+
+    #[test]
+    fn largest_operand() {
+        for bits in [8, 10] {
+            let max = (1 << bits) - 1;
+            assert!(verifies(Op::Lt, max - 1, max, bits, true));
+            assert!(verifies(Op::Lt, max, max - 1, bits, false));
+            assert!(verifies(Op::Lt, 0, max, bits, true));
+            assert!(verifies(Op::Lt, max, 0, bits, false));
+            assert!(verifies(Op::Gt, max, 0, bits, true));
+            assert!(verifies(Op::Le, max, max, bits, true));
+            assert!(!verifies(Op::Lt, 0, max, bits, false));
+        }
+    }
+
+    #[test]
+    fn operand_out_of_range() {
+        // 2^bits does not fit, whichever result is claimed.
+        for bits in [8, 10] {
+            for expected in [false, true] {
+                assert!(!verifies(Op::Lt, 1 << bits, 0, bits, expected));
+                assert!(!verifies(Op::Lt, 0, 1 << bits, bits, expected));
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Division and inversion on top of the standard gate.
+//!
+//! The inverse of `a` is a witness `inv` on a standard-gate row with
+//! `q_m = 1, q_c = -1`, which constrains `a * inv - 1 = 0`. No `inv` satisfies
+//! that for `a = 0`, so a prover claiming an inverse of zero is rejected.
+//! An honest prover asking for it gets `Error::Synthesis` from [`invert`] and
+//! [`div`] before any row is assigned.
+//!
+//! [`invert`]: DivInstructions::invert
+//! [`div`]: DivInstructions::div
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter},
+    plonk::Error,
+};
+
+use super::{
+    field::FieldChip,
+    mul::MulInstructions,
+    standard::{Coefficients, StandardChip},
+    Number,
+};
+
+pub trait DivInstructions<F: FieldExt>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Returns `1 / a`. Fails with `Error::Synthesis` if `a` is zero.
+    fn invert(&self, layouter: impl Layouter<F>, a: Self::Num) -> Result<Self::Num, Error>;
+
+    /// Returns `a / b`. Fails with `Error::Synthesis` if `b` is zero.
+    fn div(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+}
+
+impl<F: FieldExt> FieldChip<F> {
+    /// Witnesses `inv` and constrains `a * inv = 1`, without checking `inv`
+    /// first. [`DivInstructions::invert`] is the checked version; this one lets
+    /// a test play a dishonest prover.
+    pub fn assign_inverse(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Number<F>,
+        inv: Option<F>,
+    ) -> Result<Number<F>, Error> {
+        let config = self.config().standard_config.clone();
+        let standard_chip = StandardChip::<F>::construct(config, ());
+        let coeffs = Coefficients {
+            q_l: F::zero(),
+            q_r: F::zero(),
+            q_m: F::one(),
+            q_o: F::zero(),
+            q_c: -F::one(),
+        };
+        standard_chip.solve_b(layouter, a, inv, coeffs)
+    }
+}
+
+impl<F: FieldExt> DivInstructions<F> for FieldChip<F> {
+    type Num = Number<F>;
+
+    fn invert(&self, layouter: impl Layouter<F>, a: Self::Num) -> Result<Self::Num, Error> {
+        let inv = match a.0.value() {
+            Some(a) => {
+                // A zero divisor has no inverse; stop here rather than emit a
+                // witness the gate is guaranteed to reject.
+                let inv: Option<F> = a.invert().into();
+                Some(inv.ok_or(Error::Synthesis)?)
+            }
+            None => None,
+        };
+        self.assign_inverse(layouter, &a, inv)
+    }
+
+    fn div(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let b_inv = self.invert(layouter.namespace(|| "1 / b"), b)?;
+        self.mul(layouter.namespace(|| "a * (1 / b)"), a, b_inv)
+    }
+}
@@ -0,0 +1,306 @@
+//! Lookup tables witnessed at proving time.
+//!
+//! A `TableColumn` is fixed: its content is part of the verifying key. The
+//! table of a [`DynamicTableChip`] lives in `N` advice columns instead, so
+//! every proof can load a different one, e.g. a private key-value map, while
+//! the lookups still read any tuple of `N` expressions.
+//!
+//! An advice column holds more than the table: rows of other regions if the
+//! column is shared, and zeros wherever nothing is assigned. A fixed `tag`
+//! column is one on the table rows only, and every lookup carries it:
+//!
+//! ```text
+//! (q, q * x_1, ..., q * x_N)  in  (tag, tag * t_1, ..., tag * t_N)
+//! ```
+//!
+//! An enabled lookup (`q = 1`) must hit a tagged row, while a disabled one
+//! reads all zeros, which every untagged row provides.
+//!
+//! | t_1    | ... | t_N    | tag |
+//! |--------|-----|--------|-----|
+//! | r_1[1] | ... | r_1[N] | 1   |
+//! | ...    | ... | ...    | ... |
+//! | r_m[1] | ... | r_m[N] | 1   |
+//! | r_1[1] | ... | r_1[N] | 1   |
+//!
+//! The tag is part of the verifying key, so the table always fills `capacity`
+//! rows; unused rows repeat the first entry, which adds nothing to the table.
+//! One key therefore serves every table of at most `capacity` entries.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector, VirtualCells},
+    poly::Rotation,
+};
+
+use super::Number;
+
+pub trait DynamicTableInstructions<F: FieldExt, const N: usize>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Loads `rows` as the table, padded to `capacity` rows, and returns the
+    /// cells of the given rows.
+    ///
+    /// Fails with `Error::Synthesis` if `rows` is empty or longer than
+    /// `capacity`.
+    fn load(
+        &self,
+        layouter: impl Layouter<F>,
+        rows: &[[Option<F>; N]],
+        capacity: usize,
+    ) -> Result<Vec<[Self::Num; N]>, Error>;
+
+    /// Constrains `tuple` to be a row of the table.
+    fn lookup(&self, layouter: impl Layouter<F>, tuple: &[Self::Num; N]) -> Result<(), Error>;
+}
+
+#[derive(Clone, Debug)]
+pub struct DynamicTableConfig<const N: usize> {
+    /// The table.
+    pub table: [Column<Advice>; N],
+    /// One on the rows of the table, zero elsewhere.
+    pub tag: Column<Fixed>,
+    /// Tuples looked up by [`DynamicTableInstructions::lookup`].
+    pub query: [Column<Advice>; N],
+    pub q_lookup: Selector,
+}
+
+pub struct DynamicTableChip<F: FieldExt, const N: usize> {
+    config: DynamicTableConfig<N>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize> Chip<F> for DynamicTableChip<F, N> {
+    type Config = DynamicTableConfig<N>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const N: usize> DynamicTableChip<F, N> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the table in `table` and the lookup of tuples copied into
+    /// `query`. Both may share columns with other chips.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        table: [Column<Advice>; N],
+        query: [Column<Advice>; N],
+    ) -> <Self as Chip<F>>::Config {
+        for column in table.iter().chain(query.iter()) {
+            meta.enable_equality(*column);
+        }
+        let tag = meta.fixed_column();
+        let q_lookup = meta.complex_selector();
+
+        let config = DynamicTableConfig {
+            table,
+            tag,
+            query,
+            q_lookup,
+        };
+        Self::configure_lookup(meta, &config, "dynamic table", |meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let tuple = query.map(|column| meta.query_advice(column, Rotation::cur()));
+            (q_lookup, tuple)
+        });
+        config
+    }
+
+    /// Registers a lookup of the tuple returned by `inputs` into the table of
+    /// `config`, on the rows where its first element, a selector expression,
+    /// is one. The selector must be a `complex_selector`.
+    pub fn configure_lookup(
+        meta: &mut ConstraintSystem<F>,
+        config: &DynamicTableConfig<N>,
+        name: &'static str,
+        inputs: impl FnOnce(&mut VirtualCells<'_, F>) -> (Expression<F>, [Expression<F>; N]),
+    ) {
+        meta.lookup_any(name, |meta| {
+            let (q, tuple) = inputs(meta);
+            let tag = meta.query_fixed(config.tag, Rotation::cur());
+
+            let mut map = vec![(q.clone(), tag.clone())];
+            for (input, column) in tuple.into_iter().zip(config.table) {
+                let entry = meta.query_advice(column, Rotation::cur());
+                map.push((q.clone() * input, tag.clone() * entry));
+            }
+            map
+        });
+    }
+}
+
+impl<F: FieldExt, const N: usize> DynamicTableInstructions<F, N> for DynamicTableChip<F, N> {
+    type Num = Number<F>;
+
+    fn load(
+        &self,
+        mut layouter: impl Layouter<F>,
+        rows: &[[Option<F>; N]],
+        capacity: usize,
+    ) -> Result<Vec<[Self::Num; N]>, Error> {
+        if rows.is_empty() || rows.len() > capacity {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+
+        layouter.assign_region(
+            || "dynamic table",
+            |mut region: Region<'_, F>| {
+                let mut cells = vec![];
+                for offset in 0..capacity {
+                    region.assign_fixed(|| "tag", config.tag, offset, || Ok(F::one()))?;
+
+                    let row = rows.get(offset).unwrap_or(&rows[0]);
+                    let mut entries = vec![];
+                    for (value, column) in row.iter().zip(config.table) {
+                        let cell = region.assign_advice(
+                            || "entry",
+                            column,
+                            offset,
+                            || value.ok_or(Error::Synthesis),
+                        )?;
+                        entries.push(Number(cell));
+                    }
+                    if offset < rows.len() {
+                        cells.push(entries.try_into().unwrap());
+                    }
+                }
+                Ok(cells)
+            },
+        )
+    }
+
+    fn lookup(&self, mut layouter: impl Layouter<F>, tuple: &[Self::Num; N]) -> Result<(), Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "lookup",
+            |mut region: Region<'_, F>| {
+                config.q_lookup.enable(&mut region, 0)?;
+                for (x, column) in tuple.iter().zip(config.query) {
+                    x.0.copy_advice(|| "x", &mut region, column, 0)?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        pairing::bn256::Fr as Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    use super::*;
+    use crate::gadgets::{
+        field::{FieldChip, FieldConfig},
+        test_utils::mock_verifies,
+    };
+
+    /// Looks up the public `(key, value)` pairs, in rows `2i` and `2i + 1`,
+    /// in a private map of up to 16 entries sharing the field chip's columns.
+    struct MapCircuit {
+        map: Vec<[Option<Fp>; 2]>,
+        queries: usize,
+    }
+
+    impl Circuit<Fp> for MapCircuit {
+        type Config = (FieldConfig, DynamicTableConfig<2>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                map: vec![[None; 2]; self.map.len()],
+                queries: self.queries,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [meta.advice_column(), meta.advice_column()];
+            let instance = meta.instance_column();
+            (
+                FieldChip::configure(meta, advice, instance),
+                DynamicTableChip::configure(meta, advice, advice),
+            )
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let field_chip = FieldChip::construct(config.0, ());
+            let table_chip = DynamicTableChip::<Fp, 2>::construct(config.1, ());
+            table_chip.load(layouter.namespace(|| "map"), &self.map, 16)?;
+
+            for i in 0..self.queries {
+                let key = field_chip.load_instance(layouter.namespace(|| "key"), 2 * i)?;
+                let value = field_chip.load_instance(layouter.namespace(|| "value"), 2 * i + 1)?;
+                table_chip.lookup(layouter.namespace(|| "map[key] = value"), &[key, value])?;
+            }
+            Ok(())
+        }
+    }
+
+    fn verifies(map: &[(u64, u64)], queries: &[(u64, u64)]) -> bool {
+        let circuit = MapCircuit {
+            map: map
+                .iter()
+                .map(|(key, value)| [Some(Fp::from(*key)), Some(Fp::from(*value))])
+                .collect(),
+            queries: queries.len(),
+        };
+        let public_inputs = queries
+            .iter()
+            .flat_map(|(key, value)| [Fp::from(*key), Fp::from(*value)])
+            .collect();
+        mock_verifies(6, &circuit, vec![public_inputs])
+    }
+
+    const BALANCES: [(u64, u64); 4] = [(1, 100), (2, 250), (7, 0), (42, 9)];
+
+    #[test]
+    fn entries() {
+        assert!(verifies(&BALANCES, &[(2, 250), (42, 9), (2, 250)]));
+        assert!(verifies(&BALANCES, &[(7, 0)]));
+    }
+
+    #[test]
+    fn wrong_value_or_missing_key() {
+        assert!(!verifies(&BALANCES, &[(2, 251)]));
+        assert!(!verifies(&BALANCES, &[(3, 100)]));
+    }
+
+    #[test]
+    fn untagged_rows() {
+        // The key 3 is in the shared advice column, next to a zero, once the
+        // instance cell is loaded; untagged rows are not entries.
+        assert!(!verifies(&BALANCES, &[(3, 0)]));
+    }
+
+    #[test]
+    fn padding() {
+        // Padding only repeats the first entry.
+        assert!(!verifies(&BALANCES, &[(0, 0)]));
+        assert!(verifies(&[(0, 0)], &[(0, 0)]));
+    }
+}
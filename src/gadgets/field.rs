@@ -0,0 +1,302 @@
+//! The top-level `FieldChip`, which exposes add, sub and mul over a shared pair
+//! of advice columns together with private inputs and public outputs.
+//!
+//! All three operations are rows of one standard PLONK gate (see
+//! [`super::standard`]), so the chip needs a single gate and one selector.
+//!
+//! Constants never come from the prover. `load_constant` assigns them from a
+//! fixed column enabled with `enable_constant`, and `add_constant` and
+//! `mul_constant` put them in the gate's `q_c` and `q_l` coefficients; `q_c`
+//! is that same fixed column. Either way they are part of the verifying key.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Instance},
+};
+
+use super::{
+    add::AddInstructions,
+    mul::MulInstructions,
+    standard::{Coefficients, StandardChip, StandardConfig, StandardInstructions},
+    sub::SubInstructions,
+    Number,
+};
+
+// ANCHOR: field-instructions
+pub trait FieldInstructions<F: FieldExt>:
+    AddInstructions<F> + SubInstructions<F> + MulInstructions<F>
+{
+    /// Variable representing a number.
+    type Num;
+
+    /// Loads a number into the circuit as a private input.
+    fn load_private(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Option<F>,
+    ) -> Result<<Self as FieldInstructions<F>>::Num, Error>;
+
+    /// Loads a constant into the circuit. Unlike a private input, its value is
+    /// fixed by the verifying key.
+    fn load_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        constant: F,
+    ) -> Result<<Self as FieldInstructions<F>>::Num, Error>;
+
+    /// Returns `a + constant`.
+    fn add_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        a: <Self as FieldInstructions<F>>::Num,
+        constant: F,
+    ) -> Result<<Self as FieldInstructions<F>>::Num, Error>;
+
+    /// Returns `a * constant`.
+    fn mul_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        a: <Self as FieldInstructions<F>>::Num,
+        constant: F,
+    ) -> Result<<Self as FieldInstructions<F>>::Num, Error>;
+
+    /// Exposes a number as a public input to the circuit.
+    fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        num: <Self as FieldInstructions<F>>::Num,
+        row: usize,
+    ) -> Result<(), Error>;
+}
+// ANCHOR_END: field-instructions
+
+// ANCHOR: field-config
+// The top-level config that provides all necessary columns and permutations
+// for the other configs.
+#[derive(Clone, Debug)]
+pub struct FieldConfig {
+    /// For this chip, we will use two advice columns to implement our instructions.
+    /// These are also the columns through which we communicate with other parts of
+    /// the circuit.
+    pub advice: [Column<Advice>; 2],
+
+    /// Public inputs
+    pub instance: Column<Instance>,
+
+    /// Constants loaded with `load_constant`.
+    pub constant: Column<Fixed>,
+
+    pub standard_config: StandardConfig,
+}
+// ANCHOR END: field-config
+
+// ANCHOR: field-chip
+/// The top-level chip that will implement the `FieldInstructions`.
+pub struct FieldChip<F: FieldExt> {
+    config: FieldConfig,
+    _marker: PhantomData<F>,
+}
+// ANCHOR_END: field-chip
+
+// ANCHOR: field-chip-trait-impl
+impl<F: FieldExt> Chip<F> for FieldChip<F> {
+    type Config = FieldConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+// ANCHOR_END: field-chip-trait-impl
+
+// ANCHOR: field-chip-impl
+impl<F: FieldExt> FieldChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        instance: Column<Instance>,
+    ) -> <Self as Chip<F>>::Config {
+        let constant = meta.fixed_column();
+        let standard_config = StandardChip::configure(meta, advice, constant);
+
+        meta.enable_equality(instance);
+
+        FieldConfig {
+            advice,
+            instance,
+            constant,
+            standard_config,
+        }
+    }
+
+    /// Copies the value in `row` of the instance column into the circuit, so a
+    /// public input can be used like any other number.
+    pub fn load_instance(
+        &self,
+        mut layouter: impl Layouter<F>,
+        row: usize,
+    ) -> Result<Number<F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "load instance",
+            |mut region| {
+                region
+                    .assign_advice_from_instance(
+                        || "public input",
+                        config.instance,
+                        row,
+                        config.advice[0],
+                        0,
+                    )
+                    .map(Number)
+            },
+        )
+    }
+}
+// ANCHOR_END: field-chip-impl
+
+impl<F: FieldExt> StandardInstructions<F> for FieldChip<F> {
+    type Num = Number<F>;
+    fn combine(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Option<Self::Num>,
+        coeffs: Coefficients<F>,
+    ) -> Result<Self::Num, Error> {
+        let config = self.config().standard_config.clone();
+        let standard_chip = StandardChip::<F>::construct(config, ());
+        standard_chip.combine(layouter, a, b, coeffs)
+    }
+}
+
+// ANCHOR: add-instructions-impl
+impl<F: FieldExt> AddInstructions<F> for FieldChip<F> {
+    type Num = Number<F>;
+    fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        self.combine(layouter, a, Some(b), Coefficients::add())
+    }
+}
+// ANCHOR END: add-instructions-impl
+
+impl<F: FieldExt> SubInstructions<F> for FieldChip<F> {
+    type Num = Number<F>;
+    fn sub(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        self.combine(layouter, a, Some(b), Coefficients::sub())
+    }
+}
+
+// ANCHOR: mul-instructions-impl
+impl<F: FieldExt> MulInstructions<F> for FieldChip<F> {
+    type Num = Number<F>;
+    fn mul(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        self.combine(layouter, a, Some(b), Coefficients::mul())
+    }
+}
+// ANCHOR END: mul-instructions-impl
+
+// ANCHOR: field-instructions-impl
+impl<F: FieldExt> FieldInstructions<F> for FieldChip<F> {
+    type Num = Number<F>;
+
+    fn load_private(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Option<F>,
+    ) -> Result<<Self as FieldInstructions<F>>::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "load private",
+            |mut region| {
+                region
+                    .assign_advice(
+                        || "private input",
+                        config.advice[0],
+                        0,
+                        || value.ok_or(Error::Synthesis),
+                    )
+                    .map(Number)
+            },
+        )
+    }
+
+    fn load_constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        constant: F,
+    ) -> Result<<Self as FieldInstructions<F>>::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "load constant",
+            |mut region| {
+                region
+                    .assign_advice_from_constant(|| "constant", config.advice[0], 0, constant)
+                    .map(Number)
+            },
+        )
+    }
+
+    fn add_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        a: <Self as FieldInstructions<F>>::Num,
+        constant: F,
+    ) -> Result<<Self as FieldInstructions<F>>::Num, Error> {
+        let coeffs = Coefficients::output(F::one(), F::zero(), F::zero(), constant);
+        self.combine(layouter, a, None, coeffs)
+    }
+
+    fn mul_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        a: <Self as FieldInstructions<F>>::Num,
+        constant: F,
+    ) -> Result<<Self as FieldInstructions<F>>::Num, Error> {
+        let coeffs = Coefficients::output(constant, F::zero(), F::zero(), F::zero());
+        self.combine(layouter, a, None, coeffs)
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        num: <Self as FieldInstructions<F>>::Num,
+        row: usize,
+    ) -> Result<(), Error> {
+        let config = self.config();
+
+        layouter.constrain_instance(num.0.cell(), config.instance, row)
+    }
+}
+// ANCHOR_END: field-instructions-impl
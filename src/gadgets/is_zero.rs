@@ -0,0 +1,87 @@
+//! Zero and equality tests with the inverse-witness technique.
+//!
+//! For an input `a`, the prover witnesses `inv` and the chip constrains
+//!
+//! ```text
+//! out = 1 - a * inv,    a * out = 0
+//! ```
+//!
+//! If `a = 0`, the first row forces `out = 1` whatever `inv` is. Otherwise the
+//! second row forces `out = 0`, which the first row only allows for
+//! `inv = 1 / a`. Either way `out` is the boolean `a == 0` and the prover has
+//! no choice left. `is_equal(a, b)` is `is_zero(a - b)`.
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter},
+    plonk::Error,
+};
+
+use super::{
+    field::{FieldChip, FieldInstructions},
+    standard::{Coefficients, StandardChip, StandardInstructions},
+    sub::SubInstructions,
+    Number,
+};
+
+pub trait IsZeroInstructions<F: FieldExt>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Returns 1 if `a` is zero and 0 otherwise.
+    fn is_zero(&self, layouter: impl Layouter<F>, a: Self::Num) -> Result<Self::Num, Error>;
+
+    /// Returns 1 if `a == b` and 0 otherwise.
+    fn is_equal(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+}
+
+impl<F: FieldExt> IsZeroInstructions<F> for FieldChip<F> {
+    type Num = Number<F>;
+
+    fn is_zero(&self, mut layouter: impl Layouter<F>, a: Self::Num) -> Result<Self::Num, Error> {
+        // Zero has no inverse; any value satisfies the rows, so use zero.
+        let inv = a
+            .0
+            .value()
+            .map(|a| Option::<F>::from(a.invert()).unwrap_or_else(F::zero));
+        let inv = self.load_private(layouter.namespace(|| "witness inv"), inv)?;
+
+        // out = 1 - a * inv
+        let coeffs = Coefficients::output(F::zero(), F::zero(), -F::one(), F::one());
+        let out = self.combine(
+            layouter.namespace(|| "1 - a * inv"),
+            a.clone(),
+            Some(inv),
+            coeffs,
+        )?;
+
+        // a * out = 0
+        let config = self.config().standard_config.clone();
+        let standard_chip = StandardChip::<F>::construct(config, ());
+        let coeffs = Coefficients {
+            q_l: F::zero(),
+            q_r: F::zero(),
+            q_m: F::one(),
+            q_o: F::zero(),
+            q_c: F::zero(),
+        };
+        standard_chip.constrain(layouter.namespace(|| "a * out = 0"), &a, &out, coeffs)?;
+
+        Ok(out)
+    }
+
+    fn is_equal(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let diff = self.sub(layouter.namespace(|| "a - b"), a, b)?;
+        self.is_zero(layouter.namespace(|| "a - b == 0"), diff)
+    }
+}
@@ -0,0 +1,148 @@
+//! The 2x2 matrix multiplication chip.
+//!
+//! A product `C = A * B` takes three rows of four advice columns, one matrix
+//! per row in row-major order:
+//!
+//! | m_00 | m_01 | m_10 | m_11 | s_mul |
+//! |------|------|------|------|-------|
+//! | a_00 | a_01 | a_10 | a_11 | 1     |
+//! | b_00 | b_01 | b_10 | b_11 |       |
+//! | c_00 | c_01 | c_10 | c_11 |       |
+//!
+//! `A` and `B` are copied in, and the gate checks
+//! `c_ij = a_i0 * b_0j + a_i1 * b_1j` for all four entries.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+use super::Number;
+
+pub trait MatrixInstructions<F: FieldExt>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Returns `a * b`.
+    fn mul(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &[[Self::Num; 2]; 2],
+        b: &[[Self::Num; 2]; 2],
+    ) -> Result<[[Self::Num; 2]; 2], Error>;
+}
+
+#[derive(Clone, Debug)]
+pub struct MatrixConfig {
+    /// The entries of a matrix, row-major.
+    pub advice: [Column<Advice>; 4],
+    pub s_mul: Selector,
+}
+
+pub struct MatrixChip<F: FieldExt> {
+    config: MatrixConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for MatrixChip<F> {
+    type Config = MatrixConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> MatrixChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 4],
+    ) -> <Self as Chip<F>>::Config {
+        for column in &advice {
+            meta.enable_equality(*column);
+        }
+        let s_mul = meta.selector();
+
+        meta.create_gate("mat mul", |meta| {
+            let s_mul = meta.query_selector(s_mul);
+            let a = advice.map(|column| meta.query_advice(column, Rotation::cur()));
+            let b = advice.map(|column| meta.query_advice(column, Rotation::next()));
+            let c = advice.map(|column| meta.query_advice(column, Rotation(2)));
+
+            (0..4)
+                .map(|k| {
+                    let (i, j) = (k / 2, k % 2);
+                    let dot =
+                        a[2 * i].clone() * b[j].clone() + a[2 * i + 1].clone() * b[2 + j].clone();
+                    s_mul.clone() * (dot - c[k].clone())
+                })
+                .collect::<Vec<_>>()
+        });
+
+        MatrixConfig { advice, s_mul }
+    }
+}
+
+impl<F: FieldExt> MatrixInstructions<F> for MatrixChip<F> {
+    type Num = Number<F>;
+
+    fn mul(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &[[Self::Num; 2]; 2],
+        b: &[[Self::Num; 2]; 2],
+    ) -> Result<[[Self::Num; 2]; 2], Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "mat mul",
+            |mut region: Region<'_, F>| {
+                config.s_mul.enable(&mut region, 0)?;
+                for (row, (m, name)) in [(a, "a"), (b, "b")].iter().enumerate() {
+                    for (k, column) in config.advice.iter().enumerate() {
+                        m[k / 2][k % 2].0.copy_advice(
+                            || format!("{}_{}{}", name, k / 2, k % 2),
+                            &mut region,
+                            *column,
+                            row,
+                        )?;
+                    }
+                }
+
+                let mut c = vec![];
+                for (k, column) in config.advice.iter().enumerate() {
+                    let (i, j) = (k / 2, k % 2);
+                    let value = a[i][0].0.value().and_then(|a0| {
+                        let a1 = a[i][1].0.value()?;
+                        let b0 = b[0][j].0.value()?;
+                        let b1 = b[1][j].0.value()?;
+                        Some(*a0 * b0 + *a1 * b1)
+                    });
+                    let cell = region.assign_advice(
+                        || format!("c_{}{}", i, j),
+                        *column,
+                        2,
+                        || value.ok_or(Error::Synthesis),
+                    )?;
+                    c.push(Number(cell));
+                }
+                Ok([[c[0].clone(), c[1].clone()], [c[2].clone(), c[3].clone()]])
+            },
+        )
+    }
+}
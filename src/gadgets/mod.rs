@@ -0,0 +1,46 @@
+//! Reusable chips for field arithmetic.
+//!
+//! [`add::AddChip`] sums `N` numbers in one row with its own selector and gate.
+//! [`standard::StandardChip`] covers addition, subtraction, multiplication,
+//! scaling and constants with one gate driven by fixed coefficient columns;
+//! [`field::FieldChip`] is built on it, implements the instruction traits of
+//! [`add`], [`sub`] and [`mul`], and offers everything behind
+//! [`field::FieldInstructions`] so a circuit only has to configure one chip.
+//! [`sub::SubChip`] and [`mul::MulChip`] are thin wrappers over the same gate.
+//! [`range::RangeCheckChip`] bounds any of these numbers to `n` bits, and
+//! [`compare::CompareChip`] compares numbers bounded that way.
+//! [`bits::BitsChip`] splits a number into constrained bits,
+//! [`bitwise::BitwiseTableChip`] provides lookup tables for bitwise
+//! operations that [`bitwise::BitwiseChip`] applies to u64 words chunk by
+//! chunk, and [`boolean`] and [`is_zero`] add flags and branching to
+//! `FieldChip`. [`uint::UintChip`] adds and multiplies with `u32` and `u64`
+//! overflow semantics. [`recurrence::RecurrenceChip`] proves linear
+//! recurrences such as the Fibonacci sequence, and [`matrix::MatrixChip`]
+//! multiplies 2x2 matrices. [`dynamic_table::DynamicTableChip`] looks tuples
+//! up in a table witnessed in advice columns, so it can change per proof.
+//! All of them pass values around as [`Number`]s.
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::AssignedCell};
+
+pub mod add;
+pub mod bits;
+pub mod bitwise;
+pub mod boolean;
+pub mod compare;
+pub mod div;
+pub mod dynamic_table;
+pub mod field;
+pub mod is_zero;
+pub mod matrix;
+pub mod mul;
+pub mod range;
+pub mod recurrence;
+pub mod standard;
+pub mod sub;
+#[cfg(test)]
+pub(crate) mod test_utils;
+pub mod uint;
+
+/// A variable representing a number.
+#[derive(Clone, Debug)]
+pub struct Number<F: FieldExt>(pub AssignedCell<F, F>);
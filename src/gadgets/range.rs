@@ -0,0 +1,335 @@
+//! The range-check chip: proves `x < 2^n` for any `n` by splitting `x` into
+//! `L`-bit limbs (`limb_bits`) that are looked up in a table holding `0..2^L`.
+//!
+//! The limbs are not stored directly. The region holds the running sum
+//! `z_0 = x`, `z_{i+1} = (z_i - limb_i) / 2^L`, so `limb_i = z_i - 2^L * z_{i+1}`
+//! is an expression of two neighbouring rows, and the last `z` is the
+//! constant zero:
+//!
+//! | z       | q_range | shift         |
+//! |---------|---------|---------------|
+//! | x       | 1       | 1             |
+//! | z_1     | 1       | 1             |
+//! | ...     | ...     | ...           |
+//! | z_{m-1} | 1       | 2^(L - r)     |
+//! | 0       |         |               |
+//!
+//! When `n` is not a multiple of `L`, the top limb must be shorter than the
+//! others: it holds `r = n mod L` bits. A second lookup checks `limb * shift`,
+//! which is in the table only if `limb < 2^r`. For the full limbs `shift` is
+//! one and the second lookup repeats the first.
+//!
+//! The table lives in a `TableColumn` passed to [`RangeCheckChip::configure`],
+//! so several range-check chips, or other lookups of small values, can share
+//! it. It must be filled once per circuit with [`RangeCheckChip::load_table`].
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector, TableColumn},
+    poly::Rotation,
+};
+
+use super::Number;
+
+pub trait RangeCheckInstructions<F: FieldExt>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Constrains `a < 2^bits`.
+    ///
+    /// A value that does not fit is still assigned, so the proof fails rather
+    /// than synthesis. Fails with `Error::Synthesis` if `bits` is so large that
+    /// `2^bits` wraps around the field.
+    fn range_check(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        bits: usize,
+    ) -> Result<(), Error>;
+}
+
+#[derive(Clone, Debug)]
+pub struct RangeCheckConfig {
+    /// The running sum.
+    pub z: Column<Advice>,
+    pub q_range: Selector,
+    /// `2^(L - r)` on the row of a short top limb, one on the other rows.
+    pub shift: Column<Fixed>,
+    /// Constant column holding the final zero of the running sum.
+    pub constant: Column<Fixed>,
+    /// Holds `0..2^limb_bits`.
+    pub table: TableColumn,
+    pub limb_bits: usize,
+}
+
+pub struct RangeCheckChip<F: FieldExt> {
+    config: RangeCheckConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for RangeCheckChip<F> {
+    type Config = RangeCheckConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> RangeCheckChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures limbs of `limb_bits` bits looked up in `table`. The table
+    /// has `2^limb_bits` rows, which must fit in the circuit.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        z: Column<Advice>,
+        table: TableColumn,
+        limb_bits: usize,
+    ) -> <Self as Chip<F>>::Config {
+        meta.enable_equality(z);
+
+        let q_range = meta.complex_selector();
+        let shift = meta.fixed_column();
+        let constant = meta.fixed_column();
+        meta.enable_constant(constant);
+
+        let two_pow_limb = F::from(1 << limb_bits);
+
+        meta.lookup("range limb", |meta| {
+            let q_range = meta.query_selector(q_range);
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+            let limb = z_cur - z_next * two_pow_limb;
+            vec![(q_range * limb, table)]
+        });
+
+        meta.lookup("range short limb", |meta| {
+            let q_range = meta.query_selector(q_range);
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+            let shift = meta.query_fixed(shift, Rotation::cur());
+            let limb = z_cur - z_next * two_pow_limb;
+            vec![(q_range * limb * shift, table)]
+        });
+
+        RangeCheckConfig {
+            z,
+            q_range,
+            shift,
+            constant,
+            table,
+            limb_bits,
+        }
+    }
+
+    /// Fills the table with `0..2^limb_bits`.
+    pub fn load_table(&self, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let config = self.config();
+        layouter.assign_table(
+            || "range",
+            |mut table| {
+                for value in 0..1usize << config.limb_bits {
+                    table.assign_cell(
+                        || "value",
+                        config.table,
+                        value,
+                        || Ok(F::from(value as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Splits the low `limb_bits * num_limbs` bits of `value` into `num_limbs`
+/// little-endian limbs. Bits above them are dropped.
+pub fn decompose<F: FieldExt>(value: &F, limb_bits: usize, num_limbs: usize) -> Vec<u64> {
+    let repr = value.to_repr();
+    let bytes = repr.as_ref();
+    let bit = |i: usize| {
+        bytes
+            .get(i / 8)
+            .map_or(0, |byte| (byte >> (i % 8)) as u64 & 1)
+    };
+    (0..num_limbs)
+        .map(|limb| (0..limb_bits).fold(0, |acc, i| acc | bit(limb * limb_bits + i) << i))
+        .collect()
+}
+
+/// Returns whether `value`, read as an integer, is below `2^bits`.
+pub fn fits<F: FieldExt>(value: &F, bits: usize) -> bool {
+    let repr = value.to_repr();
+    repr.as_ref().iter().enumerate().all(|(i, byte)| {
+        let low_bits = bits.saturating_sub(8 * i).min(8);
+        low_bits == 8 || byte >> low_bits == 0
+    })
+}
+
+impl<F: FieldExt> RangeCheckInstructions<F> for RangeCheckChip<F> {
+    type Num = Number<F>;
+
+    fn range_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Self::Num,
+        bits: usize,
+    ) -> Result<(), Error> {
+        // Above this, the limbs could sum to x plus a multiple of the modulus.
+        if bits > F::CAPACITY as usize {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+        let limb_bits = config.limb_bits;
+        let num_limbs = (bits + limb_bits - 1) / limb_bits;
+        let short_bits = bits % limb_bits;
+
+        let two_pow_limb_inv = F::from(1 << limb_bits).invert().unwrap();
+        let limbs = a.0.value().map(|a| decompose(a, limb_bits, num_limbs));
+
+        layouter.assign_region(
+            || "range check",
+            |mut region: Region<'_, F>| {
+                let mut z = a.0.copy_advice(|| "z_0", &mut region, config.z, 0)?;
+                if num_limbs == 0 {
+                    return region.constrain_constant(z.cell(), F::zero());
+                }
+                for i in 0..num_limbs {
+                    config.q_range.enable(&mut region, i)?;
+                    let shift = if i == num_limbs - 1 && short_bits != 0 {
+                        F::from(1 << (limb_bits - short_bits))
+                    } else {
+                        F::one()
+                    };
+                    region.assign_fixed(|| "shift", config.shift, i, || Ok(shift))?;
+
+                    if i == num_limbs - 1 {
+                        break;
+                    }
+                    let value = z.value().and_then(|z| {
+                        limbs
+                            .as_ref()
+                            .map(|limbs| (*z - F::from(limbs[i])) * two_pow_limb_inv)
+                    });
+                    z = region.assign_advice(
+                        || format!("z_{}", i + 1),
+                        config.z,
+                        i + 1,
+                        || value.ok_or(Error::Synthesis),
+                    )?;
+                }
+                // For an x that fits, the top limb is all that is left of the
+                // running sum; otherwise the last lookup fails.
+                region.assign_advice_from_constant(|| "z_m", config.z, num_limbs, F::zero())?;
+                Ok(())
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        pairing::bn256::Fr as Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    use super::*;
+    use crate::gadgets::{
+        field::{FieldChip, FieldConfig, FieldInstructions},
+        test_utils::mock_verifies,
+    };
+
+    /// Range-checks a private `a` to `bits` bits with 8-bit limbs.
+    #[derive(Default)]
+    struct RangeCircuit {
+        a: Option<Fp>,
+        bits: usize,
+    }
+
+    impl Circuit<Fp> for RangeCircuit {
+        type Config = (FieldConfig, RangeCheckConfig);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                bits: self.bits,
+                ..Self::default()
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [meta.advice_column(), meta.advice_column()];
+            let instance = meta.instance_column();
+            let table = meta.lookup_table_column();
+            (
+                FieldChip::configure(meta, advice, instance),
+                RangeCheckChip::configure(meta, advice[0], table, 8),
+            )
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let field_chip = FieldChip::construct(config.0, ());
+            let range_chip = RangeCheckChip::construct(config.1, ());
+            range_chip.load_table(layouter.namespace(|| "range table"))?;
+
+            let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+            range_chip.range_check(layouter.namespace(|| "a < 2^bits"), &a, self.bits)
+        }
+    }
+
+    fn verifies(a: u64, bits: usize) -> bool {
+        let circuit = RangeCircuit {
+            a: Some(Fp::from(a)),
+            bits,
+        };
+        mock_verifies(9, &circuit, vec![vec![]])
+    }
+
+    #[test]
+    fn full_limbs() {
+        assert!(verifies(65535, 16));
+        assert!(!verifies(65536, 16));
+    }
+
+    #[test]
+    fn short_top_limb() {
+        assert!(verifies(1023, 10));
+        assert!(!verifies(1024, 10));
+    }
+
+    #[test]
+    fn u64_words() {
+        assert!(verifies(u64::MAX, 64));
+        assert!(!verifies(u64::MAX, 63));
+    }
+
+    #[test]
+    fn wider_than_the_field() {
+        // bn256's scalar field holds 253 bits.
+        let circuit = RangeCircuit {
+            a: Some(Fp::from(1)),
+            bits: 254,
+        };
+        assert!(MockProver::run(9, &circuit, vec![vec![]]).is_err());
+    }
+}
@@ -0,0 +1,290 @@
+//! The linear recurrence chip: `x_n = c_1 x_{n-1} + ... + c_K x_{n-K}`.
+//!
+//! One chip of order `K` covers Fibonacci (`K = 2`, `c = [1, 1]`), Lucas (the
+//! same with `x = 2, 1, ...`), Pell (`c = [2, 1]`) and Tribonacci (`K = 3`,
+//! `c = [1, 1, 1]`). The whole sequence is one region with a row per step. A
+//! row holds the window of the last `K` elements, the new element and the
+//! coefficients:
+//!
+//! | w_0     | ... | w_{K-1}   | out     | c_1 | ... | c_K | s_rec | s_shift |
+//! |---------|-----|-----------|---------|-----|-----|-----|-------|---------|
+//! | x_0     | ... | x_{K-1}   | x_K     | c_1 | ... | c_K | 1     | 1       |
+//! | x_1     | ... | x_K       | x_{K+1} | c_1 | ... | c_K | 1     | 1       |
+//! | ...     | ... | ...       | ...     | ... | ... | ... | 1     |         |
+//!
+//! `s_rec` checks the recurrence on a row, and `s_shift` moves the window and
+//! the coefficients to the next row, so the region needs no copy constraints
+//! besides those of its first row. That row takes the coefficients either from
+//! the constant column, which binds them in the verifying key, or from the
+//! instance column, which makes them public inputs; see
+//! [`RecurrenceCoefficients`].
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+
+use super::Number;
+
+/// Where the coefficients `c_1, ..., c_K` of a recurrence come from.
+#[derive(Clone, Debug)]
+pub enum RecurrenceCoefficients<F: FieldExt> {
+    /// Constants of the circuit.
+    Fixed(Vec<F>),
+    /// Public inputs in `K` consecutive rows of the instance column, starting
+    /// at the given row.
+    Public(usize),
+}
+
+pub trait RecurrenceInstructions<F: FieldExt>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Returns the first `num` elements of the recurrence starting with the
+    /// private values `init`.
+    fn sequence(
+        &self,
+        layouter: impl Layouter<F>,
+        coefficients: &RecurrenceCoefficients<F>,
+        init: &[Option<F>],
+        num: usize,
+    ) -> Result<Vec<Self::Num>, Error>;
+
+    /// Exposes a number as a public input to the circuit.
+    fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        num: Self::Num,
+        row: usize,
+    ) -> Result<(), Error>;
+}
+
+#[derive(Clone, Debug)]
+pub struct RecurrenceConfig<const K: usize> {
+    /// `x_{n-K}, ..., x_{n-1}`.
+    pub window: [Column<Advice>; K],
+    /// `x_n`.
+    pub out: Column<Advice>,
+    /// `c_1, ..., c_K`.
+    pub coefficients: [Column<Advice>; K],
+    pub instance: Column<Instance>,
+    pub constant: Column<Fixed>,
+    pub s_rec: Selector,
+    pub s_shift: Selector,
+}
+
+pub struct RecurrenceChip<F: FieldExt, const K: usize> {
+    config: RecurrenceConfig<K>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const K: usize> Chip<F> for RecurrenceChip<F, K> {
+    type Config = RecurrenceConfig<K>;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt, const K: usize> RecurrenceChip<F, K> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> <Self as Chip<F>>::Config {
+        let window = [(); K].map(|_| meta.advice_column());
+        let out = meta.advice_column();
+        let coefficients = [(); K].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        let s_rec = meta.selector();
+        let s_shift = meta.selector();
+
+        for column in window.iter().chain(coefficients.iter()).chain([&out]) {
+            meta.enable_equality(*column);
+        }
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("recurrence", |meta| {
+            let s_rec = meta.query_selector(s_rec);
+            let out = meta.query_advice(out, Rotation::cur());
+            // c_i multiplies x_{n-i}, which is w_{K-i}.
+            let sum = (1..=K)
+                .map(|i| {
+                    meta.query_advice(coefficients[i - 1], Rotation::cur())
+                        * meta.query_advice(window[K - i], Rotation::cur())
+                })
+                .fold(Expression::Constant(F::zero()), |acc, term| acc + term);
+            vec![s_rec * (sum - out)]
+        });
+
+        meta.create_gate("shift", |meta| {
+            let s_shift = meta.query_selector(s_shift);
+            let mut constraints = vec![];
+            // The window moves one element along: w_j' = w_{j+1}, w_{K-1}' = out.
+            for j in 0..K {
+                let next = meta.query_advice(window[j], Rotation::next());
+                let cur = match j + 1 < K {
+                    true => meta.query_advice(window[j + 1], Rotation::cur()),
+                    false => meta.query_advice(out, Rotation::cur()),
+                };
+                constraints.push(s_shift.clone() * (next - cur));
+            }
+            // The coefficients stay the same on every row.
+            for column in coefficients {
+                let next = meta.query_advice(column, Rotation::next());
+                let cur = meta.query_advice(column, Rotation::cur());
+                constraints.push(s_shift.clone() * (next - cur));
+            }
+            constraints
+        });
+
+        RecurrenceConfig {
+            window,
+            out,
+            coefficients,
+            instance,
+            constant,
+            s_rec,
+            s_shift,
+        }
+    }
+
+    /// Assigns the coefficients of the first row.
+    fn assign_coefficients(
+        &self,
+        region: &mut Region<'_, F>,
+        coefficients: &RecurrenceCoefficients<F>,
+    ) -> Result<Vec<Option<F>>, Error> {
+        let config = self.config();
+        let mut values = vec![];
+        for (i, column) in config.coefficients.iter().enumerate() {
+            let cell = match coefficients {
+                RecurrenceCoefficients::Fixed(constants) => {
+                    let constant = *constants.get(i).ok_or(Error::Synthesis)?;
+                    region.assign_advice_from_constant(|| "c", *column, 0, constant)?
+                }
+                RecurrenceCoefficients::Public(start) => region.assign_advice_from_instance(
+                    || "c",
+                    config.instance,
+                    start + i,
+                    *column,
+                    0,
+                )?,
+            };
+            values.push(cell.value().copied());
+        }
+        Ok(values)
+    }
+}
+
+impl<F: FieldExt, const K: usize> RecurrenceInstructions<F> for RecurrenceChip<F, K> {
+    type Num = Number<F>;
+
+    fn sequence(
+        &self,
+        mut layouter: impl Layouter<F>,
+        coefficients: &RecurrenceCoefficients<F>,
+        init: &[Option<F>],
+        num: usize,
+    ) -> Result<Vec<Self::Num>, Error> {
+        if init.len() != K || num <= K {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+
+        layouter.assign_region(
+            || "recurrence",
+            |mut region: Region<'_, F>| {
+                let coeffs = self.assign_coefficients(&mut region, coefficients)?;
+
+                let mut seq = vec![];
+                for (j, (column, value)) in config.window.iter().zip(init).enumerate() {
+                    let cell = region.assign_advice(
+                        || format!("x_{}", j),
+                        *column,
+                        0,
+                        || value.ok_or(Error::Synthesis),
+                    )?;
+                    seq.push(Number(cell));
+                }
+
+                for row in 0..num - K {
+                    config.s_rec.enable(&mut region, row)?;
+                    if row + 1 < num - K {
+                        config.s_shift.enable(&mut region, row)?;
+                    }
+
+                    if row > 0 {
+                        // Filled in to satisfy the shift gate; no copies needed.
+                        for (j, column) in config.window.iter().enumerate() {
+                            let value = seq[row + j].0.value().copied();
+                            region.assign_advice(
+                                || format!("x_{}", row + j),
+                                *column,
+                                row,
+                                || value.ok_or(Error::Synthesis),
+                            )?;
+                        }
+                        for (column, value) in config.coefficients.iter().zip(&coeffs) {
+                            region.assign_advice(
+                                || "c",
+                                *column,
+                                row,
+                                || value.ok_or(Error::Synthesis),
+                            )?;
+                        }
+                    }
+
+                    let value = (1..=K).try_fold(F::zero(), |acc, i| {
+                        let x = seq[row + K - i].0.value()?;
+                        Some(acc + coeffs[i - 1]? * x)
+                    });
+                    let cell = region.assign_advice(
+                        || format!("x_{}", row + K),
+                        config.out,
+                        row,
+                        || value.ok_or(Error::Synthesis),
+                    )?;
+                    seq.push(Number(cell));
+                }
+                Ok(seq)
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        num: Self::Num,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(num.0.cell(), self.config().instance, row)
+    }
+}
+
+/// Returns the first `num` elements of `x_n = c_1 x_{n-1} + ... + c_K x_{n-K}`
+/// starting with `init`, where `K = coefficients.len() = init.len()`.
+pub fn linear_recurrence(coefficients: &[u64], init: &[u64], num: usize) -> Vec<u64> {
+    let order = coefficients.len();
+    let mut seq = init.to_vec();
+    for n in order..num {
+        let next = (1..=order).map(|i| coefficients[i - 1] * seq[n - i]).sum();
+        seq.push(next);
+    }
+    seq.truncate(num);
+    seq
+}
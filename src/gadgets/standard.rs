@@ -0,0 +1,351 @@
+//! The standard PLONK gate:
+//!
+//! ```text
+//! q_l * a + q_r * b + q_m * a * b + q_o * c + q_c = 0
+//! ```
+//!
+//! The coefficients live in fixed columns, so every row picks its own
+//! operation. This replaces a selector and a gate per operation: add, sub,
+//! mul, scaling and adding a constant are all the same gate with different
+//! coefficients.
+//!
+//! `q_o` is not a column. A row either computes `c` (`q_o = -1`, any other
+//! non-zero `q_o` is divided out before assignment) or asserts a relation
+//! (`q_o = 0`), in which case `c` is copied from the constant zero. One
+//! selector `s` switches the `c` and `q_c` terms on, and `q_c` lives in the
+//! column the chip's constants are loaded from, so the gate is
+//!
+//! ```text
+//! q_l * a + q_r * b + q_m * a * b + s * (q_c - c) = 0
+//! ```
+//!
+//! Off the gate's rows `q_l`, `q_r` and `q_m` are zero and `s` masks the
+//! constants, so those rows are unconstrained. Compared to the separate add,
+//! sub and mul chips `FieldChip` used before:
+//!
+//! |                | advice | selectors | fixed | gates |
+//! |----------------|--------|-----------|-------|-------|
+//! | add, sub, mul  | 2      | 3         | 0     | 3     |
+//! | standard gate  | 2      | 1         | 3     | 1     |
+//!
+//! Both count without the constants column, which `FieldChip` needs for
+//! `load_constant` either way.
+//!
+//! Like the other chips, `a` and `b` sit in two advice columns and `c` is on
+//! the next row of the first column:
+//!
+//! | a0  | a1 | s | q_l | q_r | q_m | q_c |
+//! |-----|----|---|-----|-----|-----|-----|
+//! | a   | b  | 1 | q_l | q_r | q_m | q_c |
+//! | c   |    |   |     |     |     |     |
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::Number;
+
+/// The coefficients of one row of the standard gate.
+#[derive(Clone, Copy, Debug)]
+pub struct Coefficients<F: FieldExt> {
+    pub q_l: F,
+    pub q_r: F,
+    pub q_m: F,
+    pub q_o: F,
+    pub q_c: F,
+}
+
+impl<F: FieldExt> Coefficients<F> {
+    /// `c = q_l * a + q_r * b + q_m * a * b + q_c`, i.e. `q_o = -1`.
+    pub fn output(q_l: F, q_r: F, q_m: F, q_c: F) -> Self {
+        Self {
+            q_l,
+            q_r,
+            q_m,
+            q_o: -F::one(),
+            q_c,
+        }
+    }
+
+    /// `c = a + b`.
+    pub fn add() -> Self {
+        Self::output(F::one(), F::one(), F::zero(), F::zero())
+    }
+
+    /// `c = a - b`.
+    pub fn sub() -> Self {
+        Self::output(F::one(), -F::one(), F::zero(), F::zero())
+    }
+
+    /// `c = a * b`.
+    pub fn mul() -> Self {
+        Self::output(F::zero(), F::zero(), F::one(), F::zero())
+    }
+
+    /// Evaluates `c` for the given `a` and `b`. Only meaningful when `q_o` is
+    /// invertible.
+    pub fn eval(&self, a: F, b: F) -> Option<F> {
+        let rest = self.q_l * a + self.q_r * b + self.q_m * a * b + self.q_c;
+        Option::from(self.q_o.invert()).map(|q_o_inv: F| -rest * q_o_inv)
+    }
+
+    /// The same row with `q_o = -1`, or unchanged when `q_o` is zero.
+    fn normalize(&self) -> Self {
+        match Option::<F>::from(self.q_o.invert()) {
+            Some(q_o_inv) => {
+                let scale = -q_o_inv;
+                Self {
+                    q_l: self.q_l * scale,
+                    q_r: self.q_r * scale,
+                    q_m: self.q_m * scale,
+                    q_o: -F::one(),
+                    q_c: self.q_c * scale,
+                }
+            }
+            None => *self,
+        }
+    }
+}
+
+pub trait StandardInstructions<F: FieldExt>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Returns the `c` that satisfies the gate for `a`, `b` and `coeffs`.
+    ///
+    /// `b` may be omitted when `q_r` and `q_m` are zero, for example when
+    /// scaling or adding a constant to `a`.
+    fn combine(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Option<Self::Num>,
+        coeffs: Coefficients<F>,
+    ) -> Result<Self::Num, Error>;
+}
+
+#[derive(Clone, Debug)]
+pub struct StandardConfig {
+    pub advice: [Column<Advice>; 2],
+    pub s_standard: Selector,
+    pub q_l: Column<Fixed>,
+    pub q_r: Column<Fixed>,
+    pub q_m: Column<Fixed>,
+    /// The constants column, shared with `load_constant`.
+    pub q_c: Column<Fixed>,
+}
+
+pub struct StandardChip<F: FieldExt> {
+    config: StandardConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for StandardChip<F> {
+    type Config = StandardConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> StandardChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// `constant` is enabled for constants and doubles as the `q_c` column.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        constant: Column<Fixed>,
+    ) -> <Self as Chip<F>>::Config {
+        for column in &advice {
+            meta.enable_equality(*column);
+        }
+        meta.enable_constant(constant);
+        // Not a factor of the whole gate, so it must not be compressed with
+        // other selectors.
+        let s_standard = meta.complex_selector();
+        let q_l = meta.fixed_column();
+        let q_r = meta.fixed_column();
+        let q_m = meta.fixed_column();
+        let q_c = constant;
+
+        meta.create_gate("standard", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let c = meta.query_advice(advice[0], Rotation::next());
+
+            let s_standard = meta.query_selector(s_standard);
+            let q_l = meta.query_fixed(q_l, Rotation::cur());
+            let q_r = meta.query_fixed(q_r, Rotation::cur());
+            let q_m = meta.query_fixed(q_m, Rotation::cur());
+            let q_c = meta.query_fixed(q_c, Rotation::cur());
+
+            vec![q_l * a.clone() + q_r * b.clone() + q_m * a * b + s_standard * (q_c - c)]
+        });
+
+        StandardConfig {
+            advice,
+            s_standard,
+            q_l,
+            q_r,
+            q_m,
+            q_c,
+        }
+    }
+
+    /// Enables the gate at offset 0 of `region` and writes `coeffs`, scaled to
+    /// `q_o = -1` unless `q_o` is zero, into its fixed columns.
+    fn assign_coefficients(
+        &self,
+        region: &mut Region<'_, F>,
+        coeffs: &Coefficients<F>,
+    ) -> Result<(), Error> {
+        let config = self.config();
+        let coeffs = coeffs.normalize();
+        config.s_standard.enable(region, 0)?;
+        for (name, column, value) in [
+            ("q_l", config.q_l, coeffs.q_l),
+            ("q_r", config.q_r, coeffs.q_r),
+            ("q_m", config.q_m, coeffs.q_m),
+            ("q_c", config.q_c, coeffs.q_c),
+        ] {
+            region.assign_fixed(|| name, column, 0, || Ok(value))?;
+        }
+        Ok(())
+    }
+
+    /// Pins `c` of a `q_o = 0` row to zero, which leaves
+    /// `q_l * a + q_r * b + q_m * a * b + q_c = 0`.
+    fn assign_zero_output(&self, region: &mut Region<'_, F>) -> Result<(), Error> {
+        let config = self.config();
+        region.assign_advice_from_constant(|| "c = 0", config.advice[0], 1, F::zero())?;
+        Ok(())
+    }
+
+    /// Witnesses `b` next to `a` on a row whose output is unused (`q_o = 0`),
+    /// so the row constrains `q_l * a + q_r * b + q_m * a * b + q_c = 0`, and
+    /// returns `b`. This is how a relation such as `a * inv = 1` is enforced.
+    pub fn solve_b(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Number<F>,
+        b: Option<F>,
+        coeffs: Coefficients<F>,
+    ) -> Result<Number<F>, Error> {
+        if coeffs.q_o != F::zero() {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+
+        layouter.assign_region(
+            || "standard constraint",
+            |mut region: Region<'_, F>| {
+                self.assign_coefficients(&mut region, &coeffs)?;
+
+                a.0.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                self.assign_zero_output(&mut region)?;
+                region
+                    .assign_advice(|| "b", config.advice[1], 0, || b.ok_or(Error::Synthesis))
+                    .map(Number)
+            },
+        )
+    }
+
+    /// Copies `a` and `b` onto a row whose output is unused (`q_o = 0`), so
+    /// the row constrains `q_l * a + q_r * b + q_m * a * b + q_c = 0`. This is
+    /// how a relation between existing numbers, such as `a * (a - 1) = 0`, is
+    /// asserted.
+    pub fn constrain(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Number<F>,
+        b: &Number<F>,
+        coeffs: Coefficients<F>,
+    ) -> Result<(), Error> {
+        if coeffs.q_o != F::zero() {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+
+        layouter.assign_region(
+            || "standard constraint",
+            |mut region: Region<'_, F>| {
+                self.assign_coefficients(&mut region, &coeffs)?;
+
+                a.0.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.0.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+                self.assign_zero_output(&mut region)
+            },
+        )
+    }
+}
+
+impl<F: FieldExt> StandardInstructions<F> for StandardChip<F> {
+    type Num = Number<F>;
+
+    fn combine(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Option<Self::Num>,
+        coeffs: Coefficients<F>,
+    ) -> Result<Self::Num, Error> {
+        if coeffs.q_o == F::zero() {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+
+        layouter.assign_region(
+            || "standard gate",
+            |mut region: Region<'_, F>| {
+                self.assign_coefficients(&mut region, &coeffs)?;
+
+                a.0.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                let b_value = match &b {
+                    Some(b) => {
+                        b.0.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+                        b.0.value().copied()
+                    }
+                    None => {
+                        // Only allowed when b does not appear in the gate, so
+                        // any value will do.
+                        if coeffs.q_r != F::zero() || coeffs.q_m != F::zero() {
+                            return Err(Error::Synthesis);
+                        }
+                        region.assign_advice(|| "b", config.advice[1], 0, || Ok(F::zero()))?;
+                        Some(F::zero())
+                    }
+                };
+
+                let value = a
+                    .0
+                    .value()
+                    .and_then(|a| b_value.and_then(|b| coeffs.eval(*a, b)));
+
+                region
+                    .assign_advice(
+                        || "c",
+                        config.advice[0],
+                        1,
+                        || value.ok_or(Error::Synthesis),
+                    )
+                    .map(Number)
+            },
+        )
+    }
+}
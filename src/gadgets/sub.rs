@@ -0,0 +1,91 @@
+//! The subtraction instructions: `out = lhs - rhs`. `FieldChip` implements them
+//! as a row of the standard gate, see [`super::standard`]; [`SubChip`] does
+//! the same on its own, for circuits that need nothing else.
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed},
+};
+
+use super::{
+    standard::{Coefficients, StandardChip, StandardConfig, StandardInstructions},
+    Number,
+};
+
+// ANCHOR: sub-instructions
+pub trait SubInstructions<F: FieldExt>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Returns `c = a - b`.
+    fn sub(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+}
+// ANCHOR_END: sub-instructions
+
+// ANCHOR: sub-chip
+/// A thin wrapper running `sub` as a row of [`StandardChip`].
+pub struct SubChip<F: FieldExt> {
+    config: StandardConfig,
+    _marker: PhantomData<F>,
+}
+// ANCHOR END: sub-chip
+
+// ANCHOR: sub-chip-trait-impl
+impl<F: FieldExt> Chip<F> for SubChip<F> {
+    type Config = StandardConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+// ANCHOR END: sub-chip-trait-impl
+
+// ANCHOR: sub-chip-impl
+impl<F: FieldExt> SubChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures a [`StandardChip`]; its config can be shared with other
+    /// chips on the same columns.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        constant: Column<Fixed>,
+    ) -> <Self as Chip<F>>::Config {
+        StandardChip::configure(meta, advice, constant)
+    }
+}
+// ANCHOR END: sub-chip-impl
+
+// ANCHOR: sub-instructions-impl
+impl<F: FieldExt> SubInstructions<F> for SubChip<F> {
+    type Num = Number<F>;
+
+    fn sub(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let standard_chip = StandardChip::<F>::construct(self.config.clone(), ());
+        standard_chip.combine(layouter, a, Some(b), Coefficients::sub())
+    }
+}
+// ANCHOR END: sub-instructions-impl
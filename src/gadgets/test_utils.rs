@@ -0,0 +1,12 @@
+//! Helpers shared by the gadget tests.
+
+use halo2_proofs::{dev::MockProver, pairing::bn256::Fr, plonk::Circuit};
+
+/// Runs `circuit` in `MockProver` and returns whether every constraint holds.
+///
+/// Panics if synthesis fails; a test expecting that calls `MockProver::run`
+/// itself.
+pub(crate) fn mock_verifies<C: Circuit<Fr>>(k: u32, circuit: &C, instances: Vec<Vec<Fr>>) -> bool {
+    let prover = MockProver::run(k, circuit, instances).unwrap();
+    prover.verify().is_ok()
+}
@@ -0,0 +1,480 @@
+//! Unsigned machine-word arithmetic: `u32` and `u64` semantics in the field.
+//!
+//! Field addition and multiplication never overflow, so a circuit built from
+//! them does not compute what Rust integer code computes. This chip splits
+//! every result at `2^n`:
+//!
+//! ```text
+//! a + b = out + 2^n * carry,    carry * (1 - carry) = 0,    out < 2^n
+//! a * b = lo + 2^n * hi,                                    lo, hi < 2^n
+//! ```
+//!
+//! `out`, `lo` and `hi` are range checked with a [`RangeCheckChip`]. For
+//! `n <= 64` neither side can wrap around the field, so the split is unique.
+//!
+//! | a0  | a1    | s_add | s_mul | two_pow_bits |
+//! |-----|-------|-------|-------|--------------|
+//! | a   | b     | 1     |       | 2^n          |
+//! | out | carry |       |       |              |
+//! | a   | b     |       | 1     | 2^n          |
+//! | lo  | hi    |       |       |              |
+//!
+//! The operands are not checked: they must be words already, i.e. results of
+//! this chip or numbers passed through [`UintInstructions::assert_word`].
+
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, Region},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::{
+    range::{RangeCheckChip, RangeCheckConfig, RangeCheckInstructions},
+    Number,
+};
+
+/// Width of a `u32`.
+pub const U32: usize = 32;
+/// Width of a `u64`.
+pub const U64: usize = 64;
+
+pub trait UintInstructions<F: FieldExt>: Chip<F> {
+    /// Variable representing a number.
+    type Num;
+
+    /// Constrains `a < 2^bits`, so it can be used as an operand.
+    fn assert_word(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        bits: usize,
+    ) -> Result<(), Error>;
+
+    /// Returns `a + b` modulo `2^bits`, like `wrapping_add`.
+    fn wrapping_add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns `a + b` and constrains it to be below `2^bits`, like
+    /// `checked_add(..).unwrap()`: an overflow leaves the circuit unsatisfied.
+    fn checked_add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns `a * b` modulo `2^bits`, like `wrapping_mul`.
+    fn wrapping_mul(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns the high and low words of the full product `a * b`.
+    fn mul_wide(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<(Self::Num, Self::Num), Error>;
+}
+
+#[derive(Clone, Debug)]
+pub struct UintConfig {
+    pub advice: [Column<Advice>; 2],
+    pub s_add: Selector,
+    pub s_mul: Selector,
+    pub two_pow_bits: Column<Fixed>,
+    pub range_config: RangeCheckConfig,
+}
+
+pub struct UintChip<F: FieldExt> {
+    config: UintConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Chip<F> for UintChip<F> {
+    type Config = UintConfig;
+    type Loaded = ();
+
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+impl<F: FieldExt> UintChip<F> {
+    pub fn construct(config: <Self as Chip<F>>::Config, _loaded: <Self as Chip<F>>::Loaded) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the add and mul gates on `advice`. The range checks go
+    /// through `range_config`, whose table the circuit has to load.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; 2],
+        range_config: RangeCheckConfig,
+    ) -> <Self as Chip<F>>::Config {
+        for column in &advice {
+            meta.enable_equality(*column);
+        }
+        let s_add = meta.selector();
+        let s_mul = meta.selector();
+        let two_pow_bits = meta.fixed_column();
+
+        meta.create_gate("uint", |meta| {
+            let s_add = meta.query_selector(s_add);
+            let s_mul = meta.query_selector(s_mul);
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let lo = meta.query_advice(advice[0], Rotation::next());
+            let hi = meta.query_advice(advice[1], Rotation::next());
+            let two_pow_bits = meta.query_fixed(two_pow_bits, Rotation::cur());
+
+            let one = Expression::Constant(F::one());
+            let split = lo + two_pow_bits * hi.clone();
+            vec![
+                s_add.clone() * (a.clone() + b.clone() - split.clone()),
+                s_add * hi.clone() * (one - hi),
+                s_mul * (a * b - split),
+            ]
+        });
+
+        UintConfig {
+            advice,
+            s_add,
+            s_mul,
+            two_pow_bits,
+            range_config,
+        }
+    }
+
+    /// Assigns `a, b` and the split `lo + 2^bits * hi` of `op(a, b)` under
+    /// `selector`, and range checks `lo`.
+    fn split(
+        &self,
+        mut layouter: impl Layouter<F>,
+        selector: Selector,
+        a: &Number<F>,
+        b: &Number<F>,
+        bits: usize,
+        op: impl Fn(u128, u128) -> u128,
+    ) -> Result<(Number<F>, Number<F>), Error> {
+        if bits == 0 || bits > U64 {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+        let two_pow_bits = F::from_u128(1 << bits);
+        let mask = (1u128 << bits) - 1;
+        let values = a.0.value().zip(b.0.value()).map(|(a, b)| {
+            let value = op(a.get_lower_128(), b.get_lower_128());
+            (F::from_u128(value & mask), F::from_u128(value >> bits))
+        });
+
+        let (lo, hi) = layouter.assign_region(
+            || "uint",
+            |mut region: Region<'_, F>| {
+                selector.enable(&mut region, 0)?;
+                region.assign_fixed(|| "2^bits", config.two_pow_bits, 0, || Ok(two_pow_bits))?;
+
+                a.0.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.0.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+
+                let lo = region.assign_advice(
+                    || "lo",
+                    config.advice[0],
+                    1,
+                    || values.map(|(lo, _)| lo).ok_or(Error::Synthesis),
+                )?;
+                let hi = region.assign_advice(
+                    || "hi",
+                    config.advice[1],
+                    1,
+                    || values.map(|(_, hi)| hi).ok_or(Error::Synthesis),
+                )?;
+                Ok((Number(lo), Number(hi)))
+            },
+        )?;
+
+        let range_chip = RangeCheckChip::<F>::construct(config.range_config.clone(), ());
+        range_chip.range_check(layouter.namespace(|| "lo < 2^bits"), &lo, bits)?;
+
+        Ok((lo, hi))
+    }
+
+    /// Returns `out` and `carry` of `a + b`.
+    fn add_with_carry(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Number<F>,
+        b: &Number<F>,
+        bits: usize,
+    ) -> Result<(Number<F>, Number<F>), Error> {
+        // The carry is boolean by the gate.
+        let s_add = self.config().s_add;
+        self.split(layouter, s_add, a, b, bits, u128::wrapping_add)
+    }
+}
+
+impl<F: FieldExt> UintInstructions<F> for UintChip<F> {
+    type Num = Number<F>;
+
+    fn assert_word(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        bits: usize,
+    ) -> Result<(), Error> {
+        if bits == 0 || bits > U64 {
+            return Err(Error::Synthesis);
+        }
+        let range_chip = RangeCheckChip::<F>::construct(self.config().range_config.clone(), ());
+        range_chip.range_check(layouter, a, bits)
+    }
+
+    fn wrapping_add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<Self::Num, Error> {
+        let (out, _) = self.add_with_carry(layouter, a, b, bits)?;
+        Ok(out)
+    }
+
+    fn checked_add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<Self::Num, Error> {
+        let (out, carry) = self.add_with_carry(layouter.namespace(|| "add"), a, b, bits)?;
+        layouter.assign_region(
+            || "no carry",
+            |mut region: Region<'_, F>| region.constrain_constant(carry.0.cell(), F::zero()),
+        )?;
+        Ok(out)
+    }
+
+    fn wrapping_mul(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<Self::Num, Error> {
+        // The high word is discarded, but it is still range checked: without
+        // that bound any `lo` would do, with `hi = (a * b - lo) / 2^bits`.
+        let (_, lo) = self.mul_wide(layouter, a, b, bits)?;
+        Ok(lo)
+    }
+
+    fn mul_wide(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Self::Num,
+        b: &Self::Num,
+        bits: usize,
+    ) -> Result<(Self::Num, Self::Num), Error> {
+        let s_mul = self.config().s_mul;
+        let (lo, hi) = self.split(
+            layouter.namespace(|| "mul"),
+            s_mul,
+            a,
+            b,
+            bits,
+            u128::wrapping_mul,
+        )?;
+        let range_chip = RangeCheckChip::<F>::construct(self.config().range_config.clone(), ());
+        range_chip.range_check(layouter.namespace(|| "hi < 2^bits"), &hi, bits)?;
+        Ok((hi, lo))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        pairing::bn256::Fr as Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    use super::*;
+    use crate::gadgets::{
+        field::{FieldChip, FieldConfig, FieldInstructions},
+        test_utils::mock_verifies,
+    };
+
+    type Config = (FieldConfig, RangeCheckConfig, UintConfig);
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let table = meta.lookup_table_column();
+        let field_config = FieldChip::configure(meta, advice, instance);
+        let range_config = RangeCheckChip::configure(meta, advice[0], table, 8);
+        let uint_config = UintChip::configure(meta, advice, range_config.clone());
+        (field_config, range_config, uint_config)
+    }
+
+    fn construct(config: Config) -> (FieldChip<Fp>, RangeCheckChip<Fp>, UintChip<Fp>) {
+        (
+            FieldChip::construct(config.0, ()),
+            RangeCheckChip::construct(config.1, ()),
+            UintChip::construct(config.2, ()),
+        )
+    }
+
+    /// Exposes the `num`-th Fibonacci number over u64 starting with `0, 1`,
+    /// with `checked_add` or `wrapping_add`.
+    #[derive(Default)]
+    struct FiboU64Circuit {
+        num: usize,
+        checked: bool,
+    }
+
+    impl Circuit<Fp> for FiboU64Circuit {
+        type Config = Config;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                num: self.num,
+                checked: self.checked,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let (field_chip, range_chip, uint_chip) = construct(config);
+            range_chip.load_table(layouter.namespace(|| "range table"))?;
+
+            let mut a = field_chip.load_constant(layouter.namespace(|| "load a"), Fp::from(0))?;
+            let mut b = field_chip.load_constant(layouter.namespace(|| "load b"), Fp::from(1))?;
+            for _ in 2..self.num {
+                let c = match self.checked {
+                    true => uint_chip.checked_add(layouter.namespace(|| "a + b"), &a, &b, U64)?,
+                    false => uint_chip.wrapping_add(layouter.namespace(|| "a + b"), &a, &b, U64)?,
+                };
+                a = b;
+                b = c;
+            }
+            field_chip.expose_public(layouter.namespace(|| "expose b"), b, 0)
+        }
+    }
+
+    /// Exposes `hi` and `lo` of the product of two private u32s.
+    #[derive(Default)]
+    struct MulU32Circuit {
+        a: Option<Fp>,
+        b: Option<Fp>,
+    }
+
+    impl Circuit<Fp> for MulU32Circuit {
+        type Config = Config;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let (field_chip, range_chip, uint_chip) = construct(config);
+            range_chip.load_table(layouter.namespace(|| "range table"))?;
+
+            let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+            let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+            uint_chip.assert_word(layouter.namespace(|| "a is a u32"), &a, U32)?;
+            uint_chip.assert_word(layouter.namespace(|| "b is a u32"), &b, U32)?;
+
+            let (hi, lo) = uint_chip.mul_wide(layouter.namespace(|| "a * b"), &a, &b, U32)?;
+            field_chip.expose_public(layouter.namespace(|| "expose hi"), hi, 0)?;
+            field_chip.expose_public(layouter.namespace(|| "expose lo"), lo, 1)
+        }
+    }
+
+    fn fibo_verifies(num: usize, checked: bool, claimed: u64) -> bool {
+        let circuit = FiboU64Circuit { num, checked };
+        mock_verifies(11, &circuit, vec![vec![Fp::from(claimed)]])
+    }
+
+    fn mul_verifies(a: u64, b: u64, hi: u64, lo: u64) -> bool {
+        let circuit = MulU32Circuit {
+            a: Some(Fp::from(a)),
+            b: Some(Fp::from(b)),
+        };
+        mock_verifies(9, &circuit, vec![vec![Fp::from(hi), Fp::from(lo)]])
+    }
+
+    fn fibo_seq(num: usize) -> Vec<u64> {
+        let mut seq = vec![0u64, 1];
+        for i in 2..num {
+            seq.push(seq[i - 2].wrapping_add(seq[i - 1]));
+        }
+        seq
+    }
+
+    #[test]
+    fn wrapping_add() {
+        let seq = fibo_seq(100);
+        assert!(fibo_verifies(100, false, seq[99]));
+        assert!(!fibo_verifies(100, false, seq[99].wrapping_add(1)));
+    }
+
+    #[test]
+    fn checked_add() {
+        // F(94) is the first Fibonacci number that overflows a u64.
+        let seq = fibo_seq(95);
+        assert!(fibo_verifies(94, true, seq[93]));
+        assert!(!fibo_verifies(95, true, seq[94]));
+    }
+
+    #[test]
+    fn mul_wide() {
+        let (a, b) = (0xdead_beef_u64, 0xcafe_babe_u64);
+        let product = a * b;
+        assert!(mul_verifies(a, b, product >> 32, product & 0xffff_ffff));
+        assert!(!mul_verifies(a, b, 0, product));
+        assert!(mul_verifies(0xffff_ffff, 0xffff_ffff, 0xffff_fffe, 1));
+    }
+
+    #[test]
+    fn operands_must_be_words() {
+        assert!(!mul_verifies(1 << 32, 1, 1, 0));
+    }
+}
@@ -0,0 +1,19 @@
+//! Shared building blocks for the Halo 2 tutorial examples.
+//!
+//! - [`batch`]: one proof and one pairing check for many instances of a circuit.
+//! - [`circuits`]: the circuits the examples prove with a real prover.
+//! - [`expr`]: arithmetic expressions compiled into `FieldChip` circuits.
+//! - [`gadgets`]: reusable chips such as `FieldChip` and its add/sub/mul chips.
+//! - [`prover`]: KZG setup, key generation, proving and verification.
+//! - [`spec`]: sequences described by a formula, proven without new chips.
+//! - [`store`]: the on-disk cache for params and keys.
+//! - [`proof_file`]: the proof container read by the `verify` binary.
+
+pub mod batch;
+pub mod circuits;
+pub mod expr;
+pub mod gadgets;
+pub mod proof_file;
+pub mod prover;
+pub mod spec;
+pub mod store;
@@ -0,0 +1,166 @@
+//! A self-describing, versioned container for a proof.
+//!
+//! A verifier needs more than the transcript bytes: it must know which circuit
+//! and `k` the proof is for, which verifying key it was made with and which
+//! public inputs it claims. A [`ProofFile`] carries all of them:
+//!
+//! ```text
+//! magic        8 bytes   b"HALOPRF\0"
+//! version      u32
+//! circuit      u32 length + UTF-8 name registered in `circuits`
+//! k            u32
+//! vk hash      64 bytes  blake2b of the serialized verifying key
+//! instances    u32 column count, then per column u32 length + 32-byte values
+//! proof        u32 length + transcript bytes
+//! ```
+//!
+//! All integers are little-endian. Lengths are untrusted: reading never
+//! allocates more than the file holds, and a length that runs past the end of
+//! the file is `InvalidData`.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use ff::PrimeField;
+use halo2_proofs::pairing::bn256::Fr;
+
+const MAGIC: &[u8; 8] = b"HALOPRF\0";
+
+/// The format version written by [`ProofFile::write`].
+pub const VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProofFile {
+    /// The name the circuit is registered under in [`crate::circuits`].
+    pub circuit: String,
+    pub k: u32,
+    /// See [`crate::prover::vk_hash`].
+    pub vk_hash: Vec<u8>,
+    /// One vector per instance column, e.g. the exposed Fibonacci result in
+    /// row 0 of the instance column of `FiboCircuit`.
+    pub instances: Vec<Vec<Fr>>,
+    pub proof: Vec<u8>,
+}
+
+fn invalid_data(msg: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: u32) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+/// Like `Read::read_exact`, but a file that ends early is invalid data.
+fn read_exact<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<()> {
+    reader.read_exact(buf).map_err(|err| match err.kind() {
+        io::ErrorKind::UnexpectedEof => invalid_data("proof file is truncated"),
+        _ => err,
+    })
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    read_exact(reader, &mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    write_u32(writer, bytes.len() as u32)?;
+    writer.write_all(bytes)
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let len = read_u32(reader)? as u64;
+    // The buffer grows as bytes arrive instead of trusting `len` upfront.
+    let mut bytes = vec![];
+    reader.by_ref().take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(invalid_data(format!(
+            "length {} runs past the end of the proof file",
+            len
+        )));
+    }
+    Ok(bytes)
+}
+
+impl ProofFile {
+    /// Returns the instance columns in the shape `create_proof` and
+    /// `verify_proof` expect.
+    pub fn instance_slices(&self) -> Vec<&[Fr]> {
+        self.instances.iter().map(|column| &column[..]).collect()
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        write_u32(writer, VERSION)?;
+        write_bytes(writer, self.circuit.as_bytes())?;
+        write_u32(writer, self.k)?;
+        write_bytes(writer, &self.vk_hash)?;
+        write_u32(writer, self.instances.len() as u32)?;
+        for column in &self.instances {
+            write_u32(writer, column.len() as u32)?;
+            for value in column {
+                writer.write_all(value.to_repr().as_ref())?;
+            }
+        }
+        write_bytes(writer, &self.proof)
+    }
+
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let mut magic = [0u8; 8];
+        read_exact(reader, &mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a proof file"));
+        }
+        let version = read_u32(reader)?;
+        if version != VERSION {
+            return Err(invalid_data(format!(
+                "unsupported proof file version {}",
+                version
+            )));
+        }
+
+        let circuit = String::from_utf8(read_bytes(reader)?)
+            .map_err(|_| invalid_data("circuit name is not UTF-8"))?;
+        let k = read_u32(reader)?;
+        let vk_hash = read_bytes(reader)?;
+
+        let columns = read_u32(reader)?;
+        let mut instances = vec![];
+        for _ in 0..columns {
+            let len = read_u32(reader)?;
+            let mut column = vec![];
+            for _ in 0..len {
+                let mut repr = <Fr as PrimeField>::Repr::default();
+                read_exact(reader, repr.as_mut())?;
+                let value = Option::from(Fr::from_repr(repr))
+                    .ok_or_else(|| invalid_data("instance value is not a field element"))?;
+                column.push(value);
+            }
+            instances.push(column);
+        }
+
+        let proof = read_bytes(reader)?;
+
+        Ok(Self {
+            circuit,
+            k,
+            vk_hash,
+            instances,
+            proof,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read(&mut BufReader::new(File::open(path)?))
+    }
+}
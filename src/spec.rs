@@ -0,0 +1,256 @@
+//! Sequences described by a formula instead of a circuit.
+//!
+//! A [`RecurrenceSpec`] is an [`Expr`] over the variables `x_1, ..., x_k`,
+//! where `x_i` stands for `x_{n-i}`. `CustomFiboCircuit` for instance is
+//!
+//! ```text
+//! x_3 + (x_2 ^ x_1)
+//! ```
+//!
+//! The same spec drives the native generator, [`RecurrenceSpec::generate`],
+//! and the circuit, [`SpecCircuit`], which synthesizes one copy of the
+//! expression per element: `+`, `-`, `*` and constants on a [`FieldChip`],
+//! `^` and `&` on the chunked lookups of a [`BitwiseChip`]. A new sequence
+//! therefore needs a string, not Rust code.
+//!
+//! The generator works on u64s and stops at the first overflow or underflow.
+//! The circuit works in the field, so the two agree as long as every
+//! intermediate value fits in a u64; the bitwise lookups reject operands that
+//! do not.
+
+use std::collections::HashMap;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use crate::{
+    expr::{Expr, ParseError},
+    gadgets::{
+        bitwise::{BitwiseChip, BitwiseConfig, BitwiseOp, BitwiseTableChip, CHUNK_BITS},
+        field::{FieldChip, FieldConfig, FieldInstructions},
+        Number,
+    },
+};
+
+/// `x_n` as an expression of `x_{n-1}, ..., x_{n-order}`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecurrenceSpec {
+    pub expr: Expr,
+    /// The largest `i` among the variables `x_i`.
+    pub order: usize,
+}
+
+/// The name of `x_{n-i}` in a spec.
+fn var(i: usize) -> String {
+    format!("x_{}", i)
+}
+
+/// Returns the largest `i` of the variables `x_i` in `expr`, or the name of
+/// the first variable that is not of that form.
+fn order(expr: &Expr) -> Result<usize, String> {
+    match expr {
+        Expr::Var(name) => match name.strip_prefix("x_").map(str::parse::<usize>) {
+            Some(Ok(i)) if i > 0 => Ok(i),
+            _ => Err(name.clone()),
+        },
+        Expr::Const(_) => Ok(0),
+        Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) | Expr::Xor(a, b) | Expr::And(a, b) => {
+            Ok(order(a)?.max(order(b)?))
+        }
+    }
+}
+
+impl RecurrenceSpec {
+    /// Parses a spec such as `x_3 + (x_2 ^ x_1)`. Its order is the largest
+    /// index used, and must be at least one.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let expr = Expr::parse(input)?;
+        let order = order(&expr).map_err(|name| ParseError {
+            position: input.find(&name).unwrap_or(0),
+            message: format!("unknown variable {:?}, expected x_1, x_2, ...", name),
+        })?;
+        if order == 0 {
+            return Err(ParseError {
+                position: 0,
+                message: "the spec does not use any previous element".to_string(),
+            });
+        }
+        Ok(Self { expr, order })
+    }
+
+    /// Returns the first `num` elements starting with `init`, which must hold
+    /// `order` elements, or `None` if one of them does not fit in a u64.
+    pub fn generate(&self, init: &[u64], num: usize) -> Option<Vec<u64>> {
+        if init.len() != self.order {
+            return None;
+        }
+        let mut seq = init.to_vec();
+        while seq.len() < num {
+            let values = (1..=self.order)
+                .map(|i| (var(i), seq[seq.len() - i]))
+                .collect::<HashMap<_, _>>();
+            seq.push(self.expr.eval_u64(&values)?);
+        }
+        seq.truncate(num);
+        Some(seq)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SpecConfig {
+    pub field_config: FieldConfig,
+    pub bitwise_config: BitwiseConfig,
+}
+
+/// Proves that the `num`-th element of the sequence defined by `spec` and the
+/// private `init` is the public input in row 0.
+#[derive(Clone, Debug)]
+pub struct SpecCircuit<F: FieldExt> {
+    pub spec: RecurrenceSpec,
+    pub init: Vec<Option<F>>,
+    pub num: usize,
+    /// Chunk width of the bitwise table; must divide 64.
+    pub chunk_bits: usize,
+}
+
+impl<F: FieldExt> SpecCircuit<F> {
+    pub fn new(spec: RecurrenceSpec, init: &[u64], num: usize) -> Self {
+        Self {
+            spec,
+            init: init.iter().map(|x| Some(F::from(*x))).collect(),
+            num,
+            chunk_bits: CHUNK_BITS,
+        }
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for SpecCircuit<F> {
+    type Config = SpecConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            spec: self.spec.clone(),
+            init: vec![None; self.init.len()],
+            num: self.num,
+            chunk_bits: self.chunk_bits,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [
+            meta.advice_column(),
+            meta.advice_column(),
+            meta.advice_column(),
+        ];
+        let instance = meta.instance_column();
+        let s_bitwise = meta.complex_selector();
+        let table = BitwiseTableChip::configure(meta);
+        let bitwise_config = BitwiseChip::configure(meta, advice, s_bitwise, table);
+        let field_config = FieldChip::configure(meta, [advice[0], advice[1]], instance);
+
+        SpecConfig {
+            field_config,
+            bitwise_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.init.len() != self.spec.order || self.num < self.spec.order {
+            return Err(Error::Synthesis);
+        }
+        let table = BitwiseTableChip::construct(config.bitwise_config.table.clone(), ()).load(
+            layouter.namespace(|| "bitwise table"),
+            &[BitwiseOp::Xor, BitwiseOp::And],
+            self.chunk_bits,
+        )?;
+        let bitwise_chip = BitwiseChip::construct(config.bitwise_config, table);
+        let field_chip = FieldChip::<F>::construct(config.field_config, ());
+
+        let mut seq = self
+            .init
+            .iter()
+            .map(|x| field_chip.load_private(layouter.namespace(|| "load x"), *x))
+            .collect::<Result<Vec<Number<F>>, _>>()?;
+        while seq.len() < self.num {
+            let vars = (1..=self.spec.order)
+                .map(|i| (var(i), seq[seq.len() - i].clone()))
+                .collect::<HashMap<_, _>>();
+            let next = self.spec.expr.synthesize_bitwise(
+                &field_chip,
+                &bitwise_chip,
+                &mut layouter,
+                &vars,
+            )?;
+            seq.push(next);
+        }
+
+        let last = seq[self.num - 1].clone();
+        field_chip.expose_public(layouter.namespace(|| "expose x_n"), last, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::pairing::bn256::Fr as Fp;
+
+    use super::*;
+    use crate::{
+        circuits::{custom_fibo::get_sequence, fibo::get_fibo_seq},
+        gadgets::test_utils::mock_verifies,
+    };
+
+    /// Checks that the `num`-th element of the sequence described by `spec`
+    /// verifies and that a wrong claim does not, and returns the sequence.
+    fn check(spec: &str, init: &[u64], num: usize) -> Vec<u64> {
+        let spec = RecurrenceSpec::parse(spec).unwrap();
+        let seq = spec.generate(init, num).unwrap();
+        let circuit = SpecCircuit::<Fp>::new(spec, init, num);
+
+        let claimed = Fp::from(seq[num - 1]);
+        assert!(mock_verifies(11, &circuit, vec![vec![claimed]]));
+        assert!(!mock_verifies(11, &circuit, vec![vec![claimed + Fp::from(1)]]));
+        seq
+    }
+
+    #[test]
+    fn fibonacci() {
+        assert_eq!(check("x_1 + x_2", &[1, 1], 20), get_fibo_seq(1, 1, 20));
+    }
+
+    #[test]
+    fn custom_fibo() {
+        assert_eq!(
+            check("x_3 + (x_2 ^ x_1)", &[1, 3, 2], 14),
+            get_sequence(1, 3, 2, 14).unwrap()
+        );
+    }
+
+    #[test]
+    fn every_operation() {
+        check("(x_1 & 255) * 3 + x_2 - 1 ^ 7", &[5, 9], 12);
+    }
+
+    #[test]
+    fn generate_overflow() {
+        // The circuit would not match the native sequence past u64.
+        let spec = RecurrenceSpec::parse("x_1 * x_1 + 1").unwrap();
+        assert_eq!(spec.generate(&[2], 8), None);
+        let spec = RecurrenceSpec::parse("x_1 - 1").unwrap();
+        assert_eq!(spec.generate(&[0], 2), None);
+    }
+
+    #[test]
+    fn variables() {
+        // Only x_1, x_2, ... are variables.
+        assert!(RecurrenceSpec::parse("x_1 + y").is_err());
+        assert!(RecurrenceSpec::parse("x_0 + 1").is_err());
+    }
+}
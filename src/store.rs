@@ -0,0 +1,274 @@
+//! On-disk cache for KZG parameters and circuit keys.
+//!
+//! Setup and key generation are the slowest steps of [`crate::prover::run`],
+//! and their output only depends on `k` and on the shape of the circuit. A
+//! [`KeyStore`] keeps them in a directory:
+//!
+//! ```text
+//! keys/
+//! ├── params-k11.bin          Params<G1Affine>, shared by all circuits with this k
+//! ├── custom_fibo-n14-k11.vk  VerifyingKey<G1Affine>
+//! └── custom_fibo-n14-k11.cs  fingerprint of the ConstraintSystem the key was made for
+//! ```
+//!
+//! The name is chosen by the caller and must cover everything that changes the
+//! layout but not the gates: `FiboCircuit` with a different `num` assigns its
+//! selectors on different rows and so needs its own key, e.g. `fibo-n12`.
+//!
+//! The fingerprint is a hash of the circuit's `ConstraintSystem` (columns,
+//! gates, lookups, permutation) and of its layout parameters, i.e. the `Debug`
+//! output of `without_witnesses()`, which keeps `num`, `chunk_bits` and the
+//! like. When a chip changes its gates, for example in `FieldChip::configure`,
+//! or a parameter changes what the fixed columns hold, the stored fingerprint
+//! no longer matches and the stale key is regenerated instead of silently
+//! producing proofs that never verify.
+//!
+//! halo2 at this version cannot serialize a `ProvingKey`, so the store keeps the
+//! verifying key and re-derives the proving key from it with `keygen_pk`, which
+//! skips the `keygen_vk` commitments.
+
+use std::{
+    fmt::{self, Debug},
+    fs,
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use halo2_proofs::{
+    pairing::bn256::{Fr, G1Affine},
+    plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error, ProvingKey, VerifyingKey},
+    poly::commitment::Params,
+};
+
+use crate::prover::{self, ProofOutput};
+
+/// Errors returned by the [`KeyStore`].
+#[derive(Debug)]
+pub enum StoreError {
+    /// Reading or writing a file failed.
+    Io(io::Error),
+    /// Key generation, proving or verification failed.
+    Halo2(Error),
+    /// The stored key was generated for a different constraint system.
+    StaleKey {
+        name: String,
+        k: u32,
+        expected: String,
+        found: String,
+    },
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Io(err) => write!(f, "key store I/O error: {}", err),
+            StoreError::Halo2(err) => write!(f, "halo2 error: {:?}", err),
+            StoreError::StaleKey {
+                name,
+                k,
+                expected,
+                found,
+            } => write!(
+                f,
+                "stored key for {} (k = {}) is stale: circuit fingerprint is {}, key was made for {}",
+                name, k, expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<io::Error> for StoreError {
+    fn from(err: io::Error) -> Self {
+        StoreError::Io(err)
+    }
+}
+
+impl From<Error> for StoreError {
+    fn from(err: Error) -> Self {
+        StoreError::Halo2(err)
+    }
+}
+
+/// Returns a hex-encoded hash of the constraint system of `C` and of the
+/// layout parameters of `circuit`.
+///
+/// Two circuits share a fingerprint when they configure the same columns,
+/// gates, lookups and permutation and agree on every field that survives
+/// `without_witnesses`, so it identifies which keys a circuit can use.
+/// Witnesses are dropped first: they do not change the key.
+pub fn fingerprint<C: Circuit<Fr> + Debug>(circuit: &C) -> String {
+    let mut cs = ConstraintSystem::<Fr>::default();
+    C::configure(&mut cs);
+    let layout = format!("{:?}\n{:?}", cs, circuit.without_witnesses());
+    to_hex(blake2b_simd::blake2b(layout.as_bytes()).as_bytes())
+}
+
+/// Formats `bytes` as lowercase hex.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// A directory holding params, verifying keys and their fingerprints.
+#[derive(Clone, Debug)]
+pub struct KeyStore {
+    dir: PathBuf,
+}
+
+impl KeyStore {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn params_path(&self, k: u32) -> PathBuf {
+        self.dir.join(format!("params-k{}.bin", k))
+    }
+
+    pub fn vk_path(&self, name: &str, k: u32) -> PathBuf {
+        self.dir.join(format!("{}-k{}.vk", name, k))
+    }
+
+    pub fn fingerprint_path(&self, name: &str, k: u32) -> PathBuf {
+        self.dir.join(format!("{}-k{}.cs", name, k))
+    }
+
+    /// Writes the params for `k`.
+    pub fn save_params(&self, k: u32, params: &Params<G1Affine>) -> Result<(), StoreError> {
+        fs::create_dir_all(&self.dir)?;
+        let mut writer = BufWriter::new(File::create(self.params_path(k))?);
+        params.write(&mut writer)?;
+        Ok(())
+    }
+
+    /// Reads the params for `k`, if they have been saved before.
+    pub fn load_params(&self, k: u32) -> Result<Option<Params<G1Affine>>, StoreError> {
+        let path = self.params_path(k);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let mut reader = BufReader::new(File::open(path)?);
+        Ok(Some(Params::read(&mut reader)?))
+    }
+
+    /// Returns the params for `k`, running the setup and saving them on a miss.
+    pub fn params(&self, k: u32) -> Result<Params<G1Affine>, StoreError> {
+        if let Some(params) = self.load_params(k)? {
+            return Ok(params);
+        }
+        let params = prover::setup(k);
+        self.save_params(k, &params)?;
+        Ok(params)
+    }
+
+    /// Writes the verifying key of `circuit` together with its fingerprint.
+    pub fn save_vk<C: Circuit<Fr> + Debug>(
+        &self,
+        name: &str,
+        k: u32,
+        circuit: &C,
+        vk: &VerifyingKey<G1Affine>,
+    ) -> Result<(), StoreError> {
+        fs::create_dir_all(&self.dir)?;
+        let mut writer = BufWriter::new(File::create(self.vk_path(name, k))?);
+        vk.write(&mut writer)?;
+        fs::write(self.fingerprint_path(name, k), fingerprint(circuit))?;
+        Ok(())
+    }
+
+    /// Reads the verifying key of `circuit`, if one has been saved before.
+    ///
+    /// Returns [`StoreError::StaleKey`] if the key was generated for a
+    /// different constraint system or layout than the one `circuit` has today.
+    pub fn load_vk<C: Circuit<Fr> + Debug>(
+        &self,
+        name: &str,
+        k: u32,
+        params: &Params<G1Affine>,
+        circuit: &C,
+    ) -> Result<Option<VerifyingKey<G1Affine>>, StoreError> {
+        let path = self.vk_path(name, k);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let expected = fingerprint(circuit);
+        let found = fs::read_to_string(self.fingerprint_path(name, k)).unwrap_or_default();
+        if found.trim() != expected {
+            return Err(StoreError::StaleKey {
+                name: name.to_string(),
+                k,
+                expected,
+                found: found.trim().to_string(),
+            });
+        }
+
+        let mut reader = BufReader::new(File::open(path)?);
+        Ok(Some(VerifyingKey::read::<_, C>(&mut reader, params)?))
+    }
+
+    /// Returns the proving key of `circuit`.
+    ///
+    /// The verifying key is loaded from the store when present and still
+    /// matches the circuit; otherwise it is regenerated and saved, replacing a
+    /// stale one.
+    pub fn proving_key<C: Circuit<Fr> + Debug>(
+        &self,
+        name: &str,
+        k: u32,
+        params: &Params<G1Affine>,
+        circuit: &C,
+    ) -> Result<ProvingKey<G1Affine>, StoreError> {
+        let vk = match self.load_vk(name, k, params, circuit) {
+            Ok(Some(vk)) => vk,
+            Ok(None) | Err(StoreError::StaleKey { .. }) => {
+                let vk = keygen_vk(params, circuit)?;
+                self.save_vk(name, k, circuit, &vk)?;
+                vk
+            }
+            Err(err) => return Err(err),
+        };
+        Ok(keygen_pk(params, vk, circuit)?)
+    }
+
+    /// Like [`prover::run`], but takes the params and keys from the store.
+    ///
+    /// The `setup` and `keygen` timings then measure loading from disk.
+    pub fn run<C: Circuit<Fr> + Debug>(
+        &self,
+        name: &str,
+        k: u32,
+        circuit: C,
+        instances: &[&[Fr]],
+    ) -> Result<ProofOutput, StoreError> {
+        let mut timings = prover::Timings::default();
+
+        let start = Instant::now();
+        let params = self.params(k)?;
+        let params_verifier =
+            prover::verifier_params(&params, prover::public_inputs_size(instances))?;
+        timings.setup = start.elapsed();
+
+        let start = Instant::now();
+        let pk = self.proving_key(name, k, &params, &circuit)?;
+        timings.keygen = start.elapsed();
+
+        let start = Instant::now();
+        let proof = prover::prove(&params, &pk, circuit, instances)?;
+        timings.prove = start.elapsed();
+
+        let start = Instant::now();
+        prover::verify(&params_verifier, pk.get_vk(), instances, &proof)?;
+        timings.verify = start.elapsed();
+
+        Ok(ProofOutput {
+            proof,
+            vk_hash: prover::vk_hash(pk.get_vk()),
+            timings,
+        })
+    }
+}